@@ -1,42 +1,132 @@
 use core::ops::{Add, Sub};
-use crate::utils::{shl_radix, shr_radix, get_precision};
+use ibig::ibig;
+use crate::utils::shl_radix;
 
-use crate::repr::FloatRepr;
+use crate::{repr::{FloatRepr, Rounding}, approx::{Approximation, RoundingDirection}, context::{Context, Signal}, error::Error};
 
-impl<const E: usize, const R: u8> Add for FloatRepr<E, R> {
-    type Output = Self;
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Add two numbers, and round the sum with a rounding mode selected at runtime
+    #[inline]
+    pub fn add_rounded(&self, rhs: &Self, rounding: Rounding) -> Self {
+        self.clone().add_with_mode(rhs.clone(), rounding as u8).value()
+    }
+
+    /// Subtract two numbers, and round the difference with a rounding mode selected at runtime
+    #[inline]
+    pub fn sub_rounded(&self, rhs: &Self, rounding: Rounding) -> Self {
+        self.clone().add_with_mode(-rhs, rounding as u8).value()
+    }
+
+    /// Add two numbers, and report whether the sum is exact and
+    /// the direction of the rounding if it's not.
+    #[inline]
+    pub fn add_approx(&self, rhs: &Self) -> Approximation<Self, RoundingDirection> {
+        self.clone().add_with_mode(rhs.clone(), R)
+    }
+
+    /// Subtract two numbers, and report whether the difference is exact and
+    /// the direction of the rounding if it's not.
+    #[inline]
+    pub fn sub_approx(&self, rhs: &Self) -> Approximation<Self, RoundingDirection> {
+        self.clone().add_with_mode(-rhs, R)
+    }
+
+    /// Add two numbers, returning an error instead of panicking if the exponent overflows
+    pub fn checked_add(&self, rhs: &Self) -> Result<Self, Error> {
+        // the addition could place a sticky digit a few digits below the precision
+        let below = self.precision.max(rhs.precision) + 3;
+        self.check_exponent_range(below, 1)?;
+        rhs.check_exponent_range(below, 1)?;
+        Ok(self.clone().try_add_with_mode(rhs.clone(), R)?.value())
+    }
+
+    /// Subtract two numbers, returning an error instead of panicking if the exponent overflows
+    #[inline]
+    pub fn checked_sub(&self, rhs: &Self) -> Result<Self, Error> {
+        self.checked_add(&-rhs)
+    }
+
+    /// Add two numbers without rounding, the precision of the result is the larger one
+    /// of the two operands, and it's grown if the exact sum needs more digits.
+    pub fn add_exact(&self, rhs: &Self) -> Self {
+        let precision = self.precision.max(rhs.precision);
+        if self.mantissa == ibig!(0) {
+            return rhs.clone().with_min_precision(precision);
+        }
+        if rhs.mantissa == ibig!(0) {
+            return self.clone().with_min_precision(precision);
+        }
+
+        // align the exponents to the lower one
+        let exponent = self.exponent.min(rhs.exponent);
+        let mut lhs_mantissa = self.mantissa.clone();
+        shl_radix::<E>(&mut lhs_mantissa, self.exponent.abs_diff(exponent));
+        let mut rhs_mantissa = rhs.mantissa.clone();
+        shl_radix::<E>(&mut rhs_mantissa, rhs.exponent.abs_diff(exponent));
+        Self::from_parts(lhs_mantissa + rhs_mantissa, exponent).with_min_precision(precision)
+    }
+
+    /// Subtract two numbers without rounding, see [Self::add_exact]
+    #[inline]
+    pub fn sub_exact(&self, rhs: &Self) -> Self {
+        self.add_exact(&-rhs)
+    }
+
+    #[inline]
+    pub(crate) fn add_with_mode(self, rhs: Self, mode: u8) -> Approximation<Self, RoundingDirection> {
+        Context::expect_in_range(self.try_add_with_mode(rhs, mode))
+    }
+
+    /// Add two numbers limited to the thread context (see [Context::bounded])
+    pub(crate) fn try_add_with_mode(self, rhs: Self, mode: u8) -> Result<Approximation<Self, RoundingDirection>, Signal> {
+        let precision = self.precision.max(rhs.precision);
+        Context::bounded(precision, mode, |precision, mode| self.add_to_precision(rhs, precision, mode))
+    }
+
+    fn add_to_precision(self, rhs: Self, precision: usize, mode: u8) -> Approximation<Self, RoundingDirection> {
+        if self.mantissa == ibig!(0) {
+            return rhs.with_precision_mode_approx(precision, mode);
+        }
+        if rhs.mantissa == ibig!(0) {
+            return self.with_precision_mode_approx(precision, mode);
+        }
 
-    fn add(self, rhs: Self) -> Self::Output {
         // put the oprand of lower exponent on the left
         let (mut lhs, mut rhs) = if self.exponent < rhs.exponent {
             (self, rhs)
         } else {
             (rhs, self)
         };
-        
-        // shortcut if lhs is too small
-        let ediff = (rhs.exponent - lhs.exponent) as usize;
-        let desire_prec = lhs.precision.max(rhs.precision) + 1; // one extra precision for rounding
-        if ediff > desire_prec {
-            return rhs;
+
+        // replace the oprand with a "sticky" digit if it's too small to affect the result
+        // other than the rounding, two extra digits are kept below the precision so that
+        // the sticky digit never fakes a tie. The oprand also has to be below the lowest digit
+        // of the other oprand, which could have more digits than the precision, otherwise it
+        // could carry into the digits that decide the rounding.
+        let lhs_top = lhs.exponent + lhs.actual_precision() as isize;
+        let rhs_top = rhs.exponent + rhs.actual_precision() as isize;
+        let floor = lhs_top.max(rhs_top) - precision as isize - 2;
+        if lhs_top < floor && lhs_top < rhs.exponent {
+            lhs.mantissa = lhs.mantissa.signum();
+            lhs.exponent = (floor - 1).min(rhs.exponent - 1);
         }
 
         // align the exponent
-        let rhs_prec = lhs.actual_precision();
-        let exponent = if ediff + rhs_prec > desire_prec {
-            debug_assert!(rhs_prec <= desire_prec);
-            let shift = desire_prec - rhs_prec;
-            shr_radix::<E>(&mut lhs.mantissa, shift);
-            shl_radix::<E>(&mut rhs.mantissa, ediff - shift);
-            rhs.exponent - (ediff - shift) as isize
-        } else {
-            shr_radix::<E>(&mut lhs.mantissa, ediff);
-            rhs.exponent
-        };
+        let ediff = (rhs.exponent - lhs.exponent) as usize;
+        shl_radix::<E>(&mut rhs.mantissa, ediff);
 
         // actuall adding
         let mantissa = lhs.mantissa + rhs.mantissa;
-        Self::from_parts_with_precision(mantissa, exponent, desire_prec - 1)
+        Self::from_parts(mantissa, lhs.exponent).with_precision_mode_approx(precision, mode)
+    }
+}
+
+impl<const E: usize, const R: u8> Add for FloatRepr<E, R> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        self.add_with_mode(rhs, R).value()
     }
 }
 
@@ -44,7 +134,7 @@ impl<const E: usize, const R: u8> Sub for FloatRepr<E, R> {
     type Output = Self;
     #[inline]
     fn sub(self, rhs: Self) -> Self::Output {
-        return self.add(-rhs);
+        self.add(-rhs)
     }
 }
 
@@ -70,4 +160,3 @@ impl<const E: usize, const R: u8> Sub<FloatRepr<E, R>> for &FloatRepr<E, R> {
         self.add(&(-rhs))
     }
 }
-
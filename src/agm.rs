@@ -0,0 +1,117 @@
+//! Implementation of the arithmetic-geometric mean and complete elliptic integrals
+
+use ibig::{IBig, ibig};
+use crate::{repr::FloatRepr, context::Context, utils::get_precision};
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the arithmetic-geometric mean of two numbers
+    ///
+    /// The precision of the result is the larger one of the two operands.
+    ///
+    /// # Panics
+    /// If any of the numbers is negative
+    pub fn agm(&self, other: &Self) -> Self {
+        Context::bounded_value(|| self.agm_unbounded(other))
+    }
+
+    fn agm_unbounded(&self, other: &Self) -> Self {
+        assert!(self.mantissa >= ibig!(0) && other.mantissa >= ibig!(0), "AGM of negative numbers");
+        let precision = self.precision.max(other.precision);
+        if self.mantissa == ibig!(0) || other.mantissa == ibig!(0) {
+            return Self::from_integer(ibig!(0), precision);
+        }
+
+        let wp = precision + Self::agm_guard(precision);
+        let a = self.clone().with_precision(wp);
+        let b = other.clone().with_precision(wp);
+        Self::agm_iterate(a, b, wp, |_, _| {}).with_precision(precision)
+    }
+
+    /// Calculate the complete elliptic integral of the first kind `K(m)`, with parameter `m = k^2`
+    ///
+    /// It's evaluated by `K(m) = pi / (2 agm(1, sqrt(1 - m)))`.
+    ///
+    /// # Panics
+    /// If the parameter is not less than 1
+    pub fn ellip_k(&self) -> Self {
+        Context::bounded_value(|| self.ellip_k_unbounded())
+    }
+
+    fn ellip_k_unbounded(&self) -> Self {
+        let precision = self.precision;
+
+        // 1 - m is calculated exactly, so that the result is accurate when m is close to 1
+        let q = -self.sub_one_exact();
+        assert!(q.mantissa > ibig!(0), "ellip_k is only defined for m < 1");
+
+        let wp = precision + Self::agm_guard(precision);
+        let one = Self::from_integer(ibig!(1), wp);
+        let agm = Self::agm_iterate(one, q.with_precision(wp).sqrt(), wp, |_, _| {});
+        let two = Self::from_integer(ibig!(2), wp);
+        (Self::pi(wp) / (two * agm)).with_precision(precision)
+    }
+
+    /// Calculate the complete elliptic integral of the second kind `E(m)`, with parameter `m = k^2`
+    ///
+    /// It's evaluated by `E(m) = K(m) (1 - sum_{n>=0} 2^(n-1) c_n^2)`, where `c_0^2 = m` and
+    /// `c_n = (a_(n-1) - b_(n-1)) / 2` are from the AGM iterations of `K(m)`.
+    ///
+    /// # Panics
+    /// If the parameter is larger than 1
+    pub fn ellip_e(&self) -> Self {
+        Context::bounded_value(|| self.ellip_e_unbounded())
+    }
+
+    fn ellip_e_unbounded(&self) -> Self {
+        let precision = self.precision;
+
+        let q = -self.sub_one_exact();
+        assert!(q.mantissa >= ibig!(0), "ellip_e is only defined for m <= 1");
+        if q.mantissa == ibig!(0) {
+            return Self::from_integer(ibig!(1), precision);
+        }
+
+        // the sum cancels with 1 when m is close to 1, where E(m) / K(m) ~ 1 / ln(4 / sqrt(1 - m))
+        let cancel = (-q.ln_f64() / 2. + 2.).max(1.);
+        let cancel = (cancel.ln() / (E as f64).ln()) as usize + 1;
+        let wp = precision + cancel + Self::agm_guard(precision + cancel);
+
+        let one = Self::from_integer(ibig!(1), wp);
+        let two = Self::from_integer(ibig!(2), wp);
+        let mut sum = &self.clone().with_precision(wp) / &two;
+        let mut scale = &one / &two;
+        let agm = Self::agm_iterate(one.clone(), q.with_precision(wp).sqrt(), wp, |a, b| {
+            // accumulate 2^(n-1) c_n^2
+            let c = &(a - b) / &two;
+            scale = &scale * &two;
+            sum = &sum + &(&scale * &(&c * &c));
+        });
+
+        let k = Self::pi(wp) / (two * agm);
+        (k * (one - sum)).with_precision(precision)
+    }
+
+    /// The guard digits for AGM iterations, which need about log2(precision) steps
+    fn agm_guard(precision: usize) -> usize {
+        get_precision::<E>(&IBig::from(precision)) + 2
+    }
+
+    /// Run the AGM iterations with working precision `wp` on positive `a` and `b`, the callback
+    /// is invoked with `(a_n, b_n)` before each step.
+    fn agm_iterate(mut a: Self, mut b: Self, wp: usize, mut step: impl FnMut(&Self, &Self)) -> Self {
+        let two = Self::from_integer(ibig!(2), wp);
+        loop {
+            step(&a, &b);
+            let diff = &a - &b;
+            let next = &(&a + &b) / &two;
+
+            // the convergence is quadratic, the error of the next mean is about (a - b)^2 / 8a
+            if diff.mantissa == ibig!(0)
+                || diff.log_radix_floor() < a.log_radix_floor() - (wp / 2) as isize - 1 {
+                return next;
+            }
+            b = (a * b).sqrt();
+            a = next;
+        }
+    }
+}
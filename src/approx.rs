@@ -0,0 +1,64 @@
+//! Types for reporting whether a result is exact
+
+/// The direction of the rounding, which tells whether the rounded result
+/// is larger or smaller than the exact result (like the ternary value in MPFR)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingDirection {
+    /// The rounded result is larger than the exact result
+    Up,
+    /// The rounded result is smaller than the exact result
+    Down,
+}
+
+/// A result of an operation that could be rounded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Approximation<T, E> {
+    /// The result is exact
+    Exact(T),
+    /// The result is inexact, with additional information about the error
+    Inexact(T, E),
+}
+
+impl<T, E> Approximation<T, E> {
+    /// Get the result, discarding the information about the error
+    #[inline]
+    pub fn value(self) -> T {
+        match self {
+            Self::Exact(v) => v,
+            Self::Inexact(v, _) => v,
+        }
+    }
+
+    /// Get a reference to the result
+    #[inline]
+    pub fn value_ref(&self) -> &T {
+        match self {
+            Self::Exact(v) => v,
+            Self::Inexact(v, _) => v,
+        }
+    }
+
+    /// Returns true if the result is exact
+    #[inline]
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Exact(_))
+    }
+
+    /// Get the information about the error, which is None if the result is exact
+    #[inline]
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            Self::Exact(_) => None,
+            Self::Inexact(_, e) => Some(e),
+        }
+    }
+
+    /// Apply a function to the result, keeping the information about the error
+    #[inline]
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Approximation<U, E> {
+        match self {
+            Self::Exact(v) => Approximation::Exact(f(v)),
+            Self::Inexact(v, e) => Approximation::Inexact(f(v), e),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Numeric comparison and hashing of float numbers, regardless of their precisions
+//!
+//! Numbers with different radices (and against primitive numbers) are also compared exactly.
+
+use core::{cmp::Ordering, hash::{Hash, Hasher}};
+use ibig::{IBig, ibig, ops::UnsignedAbs};
+use crate::{
+    repr::{FloatRepr, BinaryRepr},
+    approx::{Approximation, RoundingDirection},
+    utils::{get_precision, shl_radix},
+};
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Compare the number with a number in another radix, without any rounding
+    #[allow(non_upper_case_globals)]
+    fn cmp_radix<const E2: usize, const R2: u8>(&self, other: &FloatRepr<E2, R2>) -> Ordering {
+        // compare the signs first
+        let sign = self.mantissa.signum();
+        match sign.cmp(&other.mantissa.signum()) {
+            Ordering::Equal => {},
+            ord => return ord
+        }
+        if sign == ibig!(0) {
+            return Ordering::Equal;
+        }
+
+        let magnitude = if E == E2 {
+            self.cmp_magnitude_same_radix(other.exponent, &other.mantissa)
+        } else {
+            self.cmp_magnitude_cross_radix(other)
+        };
+        if sign > ibig!(0) { magnitude } else { magnitude.reverse() }
+    }
+
+    /// Compare the magnitudes with another nonzero number `mantissa * E^exponent`
+    fn cmp_magnitude_same_radix(&self, exponent: isize, mantissa: &IBig) -> Ordering {
+        // compare the position of the top digits first, which doesn't need big multiplications
+        let other_top = exponent + get_precision::<E>(mantissa) as isize - 1;
+        match self.log_radix_floor().cmp(&other_top) {
+            Ordering::Equal => {},
+            ord => return ord
+        }
+
+        // align the mantissas and compare them
+        let mut lhs = IBig::from(self.mantissa.clone().unsigned_abs());
+        let mut rhs = IBig::from(mantissa.clone().unsigned_abs());
+        if self.exponent > exponent {
+            shl_radix::<E>(&mut lhs, (self.exponent - exponent) as usize);
+        } else {
+            shl_radix::<E>(&mut rhs, (exponent - self.exponent) as usize);
+        }
+        lhs.cmp(&rhs)
+    }
+
+    /// Compare the magnitudes with a nonzero number in another radix
+    #[allow(non_upper_case_globals)]
+    fn cmp_magnitude_cross_radix<const E2: usize, const R2: u8>(&self, other: &FloatRepr<E2, R2>) -> Ordering {
+        // the logarithms are accurate to about the precision of f64, which decides most cases
+        let (lhs, rhs) = (self.ln_f64(), other.ln_f64());
+        let tolerance = 1e-12 * (1. + lhs.abs().max(rhs.abs()));
+        if (lhs - rhs).abs() > tolerance {
+            return lhs.partial_cmp(&rhs).unwrap();
+        }
+
+        // otherwise round the other number into this radix with enough digits to represent this number.
+        // this number is then either the rounded value or on the same side of the exact value as it,
+        // since there's no number with that many digits strictly between the exact and rounded values.
+        // the cost is bounded by the digits needed to separate the values, not by the exponents.
+        let abs = FloatRepr::<E2, R2> { mantissa: IBig::from(other.mantissa.clone().unsigned_abs()), ..other.clone() };
+        let rounded = abs.with_radix_and_precision_approx::<E>(self.actual_precision());
+        let value = rounded.value_ref();
+        match self.cmp_magnitude_same_radix(value.exponent, &value.mantissa) {
+            Ordering::Equal => match rounded {
+                Approximation::Exact(_) => Ordering::Equal,
+                Approximation::Inexact(_, RoundingDirection::Up) => Ordering::Greater,
+                Approximation::Inexact(_, RoundingDirection::Down) => Ordering::Less,
+            },
+            ord => ord,
+        }
+    }
+}
+
+#[allow(non_upper_case_globals)]
+impl<const E1: usize, const R1: u8, const E2: usize, const R2: u8> PartialEq<FloatRepr<E2, R2>> for FloatRepr<E1, R1> {
+    #[inline]
+    fn eq(&self, other: &FloatRepr<E2, R2>) -> bool {
+        if E1 == E2 {
+            // the representation is normalized, so equal values have the same parts
+            self.mantissa == other.mantissa && self.exponent == other.exponent
+        } else {
+            self.cmp_radix(other) == Ordering::Equal
+        }
+    }
+}
+
+impl<const E: usize, const R: u8> Eq for FloatRepr<E, R> {}
+
+impl<const E: usize, const R: u8> Hash for FloatRepr<E, R> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the precision is not hashed, to be consistent with the numeric equality
+        self.mantissa.hash(state);
+        self.exponent.hash(state);
+    }
+}
+
+#[allow(non_upper_case_globals)]
+impl<const E1: usize, const R1: u8, const E2: usize, const R2: u8> PartialOrd<FloatRepr<E2, R2>> for FloatRepr<E1, R1> {
+    #[inline]
+    fn partial_cmp(&self, other: &FloatRepr<E2, R2>) -> Option<Ordering> {
+        Some(self.cmp_radix(other))
+    }
+}
+
+impl<const E: usize, const R: u8> Ord for FloatRepr<E, R> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_radix(other)
+    }
+}
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Compare the numbers in a total order that also distinguishes the precision,
+    /// numerically equal values are ordered by their precisions.
+    ///
+    /// The minimum, maximum and clamping are provided by [Ord], which ignore the precision.
+    #[inline]
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other).then(self.precision.cmp(&other.precision))
+    }
+
+    /// Compare with a [f64], NaN is not comparable and infinities are larger (or smaller) than any number
+    fn cmp_f64(&self, other: f64) -> Option<Ordering> {
+        if other.is_nan() {
+            None
+        } else if other.is_infinite() {
+            Some(if other > 0. { Ordering::Less } else { Ordering::Greater })
+        } else {
+            Some(self.cmp_radix(&BinaryRepr::<R>::from(other)))
+        }
+    }
+}
+
+impl<const E: usize, const R: u8> PartialEq<f64> for FloatRepr<E, R> {
+    #[inline]
+    fn eq(&self, other: &f64) -> bool {
+        self.cmp_f64(*other) == Some(Ordering::Equal)
+    }
+}
+
+impl<const E: usize, const R: u8> PartialOrd<f64> for FloatRepr<E, R> {
+    #[inline]
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        self.cmp_f64(*other)
+    }
+}
+
+impl<const E: usize, const R: u8> PartialEq<FloatRepr<E, R>> for f64 {
+    #[inline]
+    fn eq(&self, other: &FloatRepr<E, R>) -> bool {
+        other == self
+    }
+}
+
+impl<const E: usize, const R: u8> PartialOrd<FloatRepr<E, R>> for f64 {
+    #[inline]
+    fn partial_cmp(&self, other: &FloatRepr<E, R>) -> Option<Ordering> {
+        other.cmp_f64(*self).map(Ordering::reverse)
+    }
+}
+
+macro_rules! impl_cmp_with_int {
+    ($t:ty) => {
+        impl<const E: usize, const R: u8> PartialEq<$t> for FloatRepr<E, R> {
+            #[inline]
+            fn eq(&self, other: &$t) -> bool {
+                *self == Self::from_parts(IBig::from(other.clone()), 0)
+            }
+        }
+
+        impl<const E: usize, const R: u8> PartialOrd<$t> for FloatRepr<E, R> {
+            #[inline]
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(self.cmp(&Self::from_parts(IBig::from(other.clone()), 0)))
+            }
+        }
+
+        impl<const E: usize, const R: u8> PartialEq<FloatRepr<E, R>> for $t {
+            #[inline]
+            fn eq(&self, other: &FloatRepr<E, R>) -> bool {
+                other == self
+            }
+        }
+
+        impl<const E: usize, const R: u8> PartialOrd<FloatRepr<E, R>> for $t {
+            #[inline]
+            fn partial_cmp(&self, other: &FloatRepr<E, R>) -> Option<Ordering> {
+                other.partial_cmp(self).map(Ordering::reverse)
+            }
+        }
+    };
+}
+impl_cmp_with_int!(i64);
+impl_cmp_with_int!(IBig);
@@ -0,0 +1,55 @@
+//! Mathematical constants evaluated to arbitrary precision.
+
+use ibig::{IBig, UBig, ibig};
+use crate::{repr::FloatRepr, utils::{shl_radix, get_precision}, ibig_ext::log};
+
+/// Calculate `scale * atan(1/k)` (or `scale * atanh(1/k)` if `hyperbolic` is true)
+/// in fixed point, each term is truncated.
+fn atan_recip(k: u32, scale: &IBig, hyperbolic: bool) -> IBig {
+    let k2 = IBig::from(k * k);
+    let mut power = scale / IBig::from(k);
+    let mut sum = power.clone();
+    let mut n = 1u32;
+    loop {
+        power /= &k2;
+        if power == ibig!(0) {
+            break sum;
+        }
+        let term = &power / IBig::from(2 * n + 1);
+        if n % 2 == 1 && !hyperbolic {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+        n += 1;
+    }
+}
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the constant pi with the given precision
+    pub(crate) fn pi(precision: usize) -> Self {
+        // Machin's formula: pi = 16 atan(1/5) - 4 atan(1/239), the truncation error
+        // is bounded by one unit for each term in the series
+        let terms = (precision + 2) * (log(&UBig::from(E), 25) + 1);
+        let guard = get_precision::<E>(&IBig::from(20 * terms)) + 1;
+        let digits = precision + guard;
+
+        let mut scale = ibig!(1);
+        shl_radix::<E>(&mut scale, digits);
+        let pi = atan_recip(5, &scale, false) * 16u8 - atan_recip(239, &scale, false) * 4u8;
+        Self::from_parts_with_precision(pi, -(digits as isize), precision)
+    }
+
+    /// Calculate the constant ln(2) with the given precision
+    pub(crate) fn ln2(precision: usize) -> Self {
+        // ln(2) = 2 atanh(1/3), the truncation error is bounded by one unit for each term in the series
+        let terms = (precision + 2) * (log(&UBig::from(E), 9) + 1);
+        let guard = get_precision::<E>(&IBig::from(2 * terms)) + 1;
+        let digits = precision + guard;
+
+        let mut scale = ibig!(1);
+        shl_radix::<E>(&mut scale, digits);
+        let ln2 = atan_recip(3, &scale, true) * 2u8;
+        Self::from_parts_with_precision(ln2, -(digits as isize), precision)
+    }
+}
@@ -0,0 +1,478 @@
+//! Arithmetic context carrying the precision, rounding mode, exponent range and traps,
+//! similar to the context in the `decimal` module of Python.
+
+use core::cell::{Cell, RefCell};
+use ibig::ibig;
+use crate::{
+    repr::{FloatRepr, Rounding, RoundingMode},
+    error::Error,
+    approx::{Approximation, RoundingDirection},
+    utils::shl_radix
+};
+
+/// Exceptional conditions that can be signaled by the operations in a [Context]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Signal {
+    /// The result is rounded
+    Inexact,
+    /// The exponent of the result is larger than [Context::emax]
+    Overflow,
+    /// The exponent of the result is smaller than [Context::emin], and the result is inexact
+    Underflow,
+    /// A finite number is divided by zero
+    DivisionByZero,
+    /// The operation is not defined for the operands (e.g. the square root of a negative number)
+    InvalidOperation,
+}
+
+/// A set of [Signal]s, used for the traps and flags of a [Context]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Signals {
+    /// [Signal::Inexact]
+    pub inexact: bool,
+    /// [Signal::Overflow]
+    pub overflow: bool,
+    /// [Signal::Underflow]
+    pub underflow: bool,
+    /// [Signal::DivisionByZero]
+    pub division_by_zero: bool,
+    /// [Signal::InvalidOperation]
+    pub invalid_operation: bool,
+}
+
+impl Signals {
+    /// Check whether the signal is in the set
+    #[inline]
+    pub fn contains(&self, signal: Signal) -> bool {
+        match signal {
+            Signal::Inexact => self.inexact,
+            Signal::Overflow => self.overflow,
+            Signal::Underflow => self.underflow,
+            Signal::DivisionByZero => self.division_by_zero,
+            Signal::InvalidOperation => self.invalid_operation,
+        }
+    }
+
+    /// Add the signal to the set
+    #[inline]
+    pub fn insert(&mut self, signal: Signal) {
+        match signal {
+            Signal::Inexact => self.inexact = true,
+            Signal::Overflow => self.overflow = true,
+            Signal::Underflow => self.underflow = true,
+            Signal::DivisionByZero => self.division_by_zero = true,
+            Signal::InvalidOperation => self.invalid_operation = true,
+        }
+    }
+}
+
+/// An arithmetic context, which decides how the results of the operations are rounded.
+///
+/// The operands are used exactly regardless of their own precisions, and the results are rounded
+/// to the precision of the context with its rounding mode. The exponent range limits the position
+/// of the leading digit of the results (in the radix of the numbers). Results below `radix^emin` are
+/// subnormal, they are rounded at the exponent `emin - precision + 1` and thus have fewer digits.
+///
+/// When an exceptional condition happens, the corresponding flag is set. If the signal is trapped,
+/// the operation returns the signal as an error, otherwise it returns:
+/// - [Inexact][Signal::Inexact]: the rounded result
+/// - [Overflow][Signal::Overflow]: the largest finite number with the same sign (there is no infinity)
+/// - [Underflow][Signal::Underflow]: the rounded subnormal result, which could be zero
+///
+/// Since there is no infinity or NaN, [Signal::DivisionByZero] and [Signal::InvalidOperation]
+/// are always returned as errors.
+///
+/// # Thread context
+///
+/// The operators and methods of [FloatRepr] keep their own precisions and rounding modes, but once a context
+/// is installed for the current thread with [Context::set_current], their results are limited to its exponent
+/// range the same way: the flags are set on the thread context, and the unchecked operations panic on trapped
+/// signals, while the `checked_*` variants return them as [Error](crate::Error)s. Only the final results are
+/// limited, the intermediate results inside of a function (such as [FloatRepr::exp]) and inside of the methods
+/// of a context are not. Before any context is installed, the exponents are only bounded by the range of [isize].
+///
+/// The exact operations (such as [FloatRepr::add_exact]), the conversions and the parsing never round,
+/// so they are not limited to the exponent range.
+#[derive(Clone, Debug)]
+pub struct Context {
+    /// The precision of the results
+    pub precision: usize,
+    /// The rounding mode of the results
+    pub rounding: Rounding,
+    /// The minimum exponent of the leading digit
+    pub emin: isize,
+    /// The maximum exponent of the leading digit
+    pub emax: isize,
+    /// The signals that are returned as errors
+    pub traps: Signals,
+    flags: Cell<Signals>,
+}
+
+impl Default for Context {
+    /// The default context is the same as Python: precision 28, rounding [Rounding::HalfEven],
+    /// exponent range `[-999999, 999999]`, and traps for overflow, division by zero and invalid operations
+    fn default() -> Self {
+        Self {
+            precision: 28,
+            rounding: Rounding::HalfEven,
+            emin: -999_999,
+            emax: 999_999,
+            traps: Signals { overflow: true, division_by_zero: true, invalid_operation: true, ..Signals::default() },
+            flags: Cell::new(Signals::default()),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Context> = RefCell::new(Context::default());
+    /// Whether [CURRENT] is installed by [Context::set_current], otherwise the operations of [FloatRepr] are not limited
+    static INSTALLED: Cell<bool> = const { Cell::new(false) };
+    /// The number of operations of [FloatRepr] being evaluated, only the outermost one is limited to the exponent range
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Marks an operation being evaluated on the current thread, see [Context::nested]
+struct Nested;
+
+impl Nested {
+    #[inline]
+    fn enter() -> Self {
+        DEPTH.with(|depth| depth.set(depth.get() + 1));
+        Nested
+    }
+}
+
+impl Drop for Nested {
+    #[inline]
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+impl Context {
+    /// Create a context with the given precision, other settings are the same as the default context
+    #[inline]
+    pub fn new(precision: usize) -> Self {
+        Self { precision, ..Self::default() }
+    }
+
+    /// The context of IEEE 754 binary32 format (single precision), used with [FBig](crate::FBig)
+    #[inline]
+    pub fn binary32() -> Self {
+        Self::ieee(24, 127)
+    }
+
+    /// The context of IEEE 754 binary64 format (double precision), used with [FBig](crate::FBig)
+    #[inline]
+    pub fn binary64() -> Self {
+        Self::ieee(53, 1023)
+    }
+
+    /// The context of IEEE 754 binary128 format (quadruple precision), used with [FBig](crate::FBig)
+    #[inline]
+    pub fn binary128() -> Self {
+        Self::ieee(113, 16383)
+    }
+
+    /// The context of IEEE 754 decimal32 format, used with [DBig](crate::DBig)
+    #[inline]
+    pub fn decimal32() -> Self {
+        Self::ieee(7, 96)
+    }
+
+    /// The context of IEEE 754 decimal64 format, used with [DBig](crate::DBig)
+    #[inline]
+    pub fn decimal64() -> Self {
+        Self::ieee(16, 384)
+    }
+
+    /// The context of IEEE 754 decimal128 format, used with [DBig](crate::DBig)
+    #[inline]
+    pub fn decimal128() -> Self {
+        Self::ieee(34, 6144)
+    }
+
+    /// IEEE 754 formats have `emin = 1 - emax`
+    fn ieee(precision: usize, emax: isize) -> Self {
+        Self { precision, emin: 1 - emax, emax, ..Self::default() }
+    }
+
+    /// Access the default context of the current thread
+    #[inline]
+    pub fn with_current<T>(f: impl FnOnce(&Context) -> T) -> T {
+        CURRENT.with(|ctx| f(&ctx.borrow()))
+    }
+
+    /// Replace the default context of the current thread, the operations of [FloatRepr] on this thread
+    /// are then limited to its exponent range (see [the type docs](Context#thread-context))
+    #[inline]
+    pub fn set_current(ctx: Context) {
+        CURRENT.with(|current| *current.borrow_mut() = ctx);
+        INSTALLED.with(|installed| installed.set(true));
+    }
+
+    /// Restore the default context of the current thread, the operations of [FloatRepr] on this thread
+    /// are then no longer limited to an exponent range
+    #[inline]
+    pub fn reset_current() {
+        CURRENT.with(|current| *current.borrow_mut() = Context::default());
+        INSTALLED.with(|installed| installed.set(false));
+    }
+
+    /// Evaluate the operations in the closure without limiting their results to the thread context
+    #[inline]
+    pub(crate) fn nested<T>(f: impl FnOnce() -> T) -> T {
+        let _nested = Nested::enter();
+        f()
+    }
+
+    /// Evaluate an operation of [FloatRepr], which is rounded to the precision with the rounding mode,
+    /// and limit the result to the exponent range of the thread context if it's installed.
+    ///
+    /// The operation is then evaluated with two more digits and rounded to odd (like the methods
+    /// of the context), so that the result can be rounded again without double rounding.
+    pub(crate) fn bounded<const E: usize, const R: u8>(precision: usize, mode: u8,
+        op: impl FnOnce(usize, u8) -> Approximation<FloatRepr<E, R>, RoundingDirection>)
+        -> Result<Approximation<FloatRepr<E, R>, RoundingDirection>, Signal> {
+        if !Self::is_bounded() {
+            return Ok(op(precision, mode));
+        }
+        let result = Self::nested(|| op(precision + 2, RoundingMode::ToOdd));
+        let direction = result.error().copied();
+        let limited = Self::with_current(|ctx| ctx.limit(result.value(), direction.is_some(), precision, mode))?;
+        Ok(match (limited, direction) {
+            (Approximation::Exact(value), Some(direction)) => Approximation::Inexact(value, direction),
+            (limited, _) => limited,
+        })
+    }
+
+    /// Evaluate a function of [FloatRepr], and limit the result to the exponent range of the thread context
+    /// if it's installed. Unlike [Self::bounded], the value of the function is rounded again if it's subnormal,
+    /// and it's only reported as inexact if it's changed by the exponent range.
+    pub(crate) fn bounded_value<const E: usize, const R: u8>(f: impl FnOnce() -> FloatRepr<E, R>) -> FloatRepr<E, R> {
+        if !Self::is_bounded() {
+            return f();
+        }
+        let value = Self::nested(f);
+        let precision = value.precision;
+        let limited = Self::with_current(|ctx| ctx.limit(value, false, precision, R));
+        Self::expect_in_range(limited).value()
+    }
+
+    /// Unwrap the result of [Self::bounded], panicking if a signal is trapped by the thread context
+    #[inline]
+    pub(crate) fn expect_in_range<T>(result: Result<T, Signal>) -> T {
+        result.unwrap_or_else(|signal| panic!("{}", Error::from(signal)))
+    }
+
+    /// Whether an operation being started is limited to the exponent range of the thread context
+    #[inline]
+    fn is_bounded() -> bool {
+        INSTALLED.with(Cell::get) && DEPTH.with(Cell::get) == 0
+    }
+
+    /// Get the signals that happened since the flags are cleared
+    #[inline]
+    pub fn flags(&self) -> Signals {
+        self.flags.get()
+    }
+
+    /// Reset all the flags
+    #[inline]
+    pub fn clear_flags(&self) {
+        self.flags.set(Signals::default());
+    }
+
+    /// Round the number to the context
+    pub fn round<const E: usize, const R: u8>(&self, x: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        self.finish(Approximation::Exact(x.clone()))
+    }
+
+    /// Add two numbers
+    pub fn add<const E: usize, const R: u8>(&self, lhs: &FloatRepr<E, R>, rhs: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        self.finish(Self::nested(|| self.operand(lhs).add_with_mode(self.operand(rhs), RoundingMode::ToOdd)))
+    }
+
+    /// Subtract two numbers
+    pub fn sub<const E: usize, const R: u8>(&self, lhs: &FloatRepr<E, R>, rhs: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        self.finish(Self::nested(|| self.operand(lhs).add_with_mode(-self.operand(rhs), RoundingMode::ToOdd)))
+    }
+
+    /// Multiply two numbers
+    pub fn mul<const E: usize, const R: u8>(&self, lhs: &FloatRepr<E, R>, rhs: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        self.finish(Self::nested(|| self.operand(lhs).mul_with_mode(&self.operand(rhs), RoundingMode::ToOdd)))
+    }
+
+    /// Divide two numbers
+    pub fn div<const E: usize, const R: u8>(&self, lhs: &FloatRepr<E, R>, rhs: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        if rhs.mantissa == ibig!(0) {
+            let signal = if lhs.mantissa == ibig!(0) { Signal::InvalidOperation } else { Signal::DivisionByZero };
+            self.raise(signal)?;
+            return Err(signal);
+        }
+        self.finish(Self::nested(|| self.operand(lhs).div_with_mode(self.operand(rhs), RoundingMode::ToOdd)))
+    }
+
+    /// Calculate the square root of the number
+    pub fn sqrt<const E: usize, const R: u8>(&self, x: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        if x.mantissa < ibig!(0) {
+            self.raise(Signal::InvalidOperation)?;
+            return Err(Signal::InvalidOperation);
+        }
+        self.finish(Self::nested(|| self.operand(x).sqrt_with_mode(RoundingMode::ToOdd)))
+    }
+
+    /// Calculate the exponential function of the number
+    pub fn exp<const E: usize, const R: u8>(&self, x: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        if x.mantissa == ibig!(0) {
+            return self.finish(Approximation::Exact(FloatRepr::from_integer(ibig!(1), self.precision)));
+        }
+
+        // a result far outside of the exponent range is replaced by a number that rounds the same way,
+        // so that the huge intermediate results are never evaluated
+        let log_radix = x.to_f64_estimate() / (E as f64).ln();
+        if log_radix > (self.emax + 2) as f64 {
+            return self.finish_value(FloatRepr::from_parts(ibig!(1), self.emax + 2), true);
+        }
+        let etiny = self.emin - self.precision as isize + 1;
+        if log_radix < (etiny - 3) as f64 {
+            return self.finish_value(FloatRepr::from_parts(ibig!(1), etiny - 4), true);
+        }
+        self.finish_transcendental(|wp| FloatRepr { precision: wp, ..x.clone() }.exp())
+    }
+
+    /// Calculate the natural logarithm of the number
+    pub fn ln<const E: usize, const R: u8>(&self, x: &FloatRepr<E, R>) -> Result<FloatRepr<E, R>, Signal> {
+        if x.mantissa <= ibig!(0) {
+            let signal = if x.mantissa == ibig!(0) { Signal::DivisionByZero } else { Signal::InvalidOperation };
+            self.raise(signal)?;
+            return Err(signal);
+        }
+        if x.mantissa == ibig!(1) && x.exponent == 0 {
+            return self.finish(Approximation::Exact(FloatRepr::from_integer(ibig!(0), self.precision)));
+        }
+        self.finish_transcendental(|wp| FloatRepr { precision: wp, ..x.clone() }.ln())
+    }
+
+    /// Round the number to `scale` digits after the radix point with the rounding mode of the context
+    /// (see [FloatRepr::quantize]). [Signal::InvalidOperation] is signaled if the result needs more digits
+    /// than the precision of the context, or the exponent `-scale` is out of the range `[emin - precision + 1, emax]`.
+    pub fn quantize<const E: usize, const R: u8>(&self, x: &FloatRepr<E, R>, scale: isize)
+        -> Result<FloatRepr<E, R>, Signal> {
+        let result = x.clone().with_exponent_mode_approx(-scale, self.rounding as u8);
+        let value = result.value_ref();
+        let etiny = self.emin - self.precision as isize + 1;
+        let too_long = value.mantissa != ibig!(0) && value.log_radix_floor() + scale >= self.precision as isize;
+        if too_long || -scale < etiny || -scale > self.emax {
+            self.raise(Signal::InvalidOperation)?;
+            return Err(Signal::InvalidOperation);
+        }
+        self.finish(result)
+    }
+
+    /// Convert the number to another radix, and round it to the context
+    #[allow(non_upper_case_globals)]
+    pub fn convert<const E: usize, const NewE: usize, const R: u8>(&self, x: &FloatRepr<E, R>)
+        -> Result<FloatRepr<NewE, R>, Signal> {
+        self.finish(x.clone().with_radix_mode_approx::<NewE>(self.precision + 2, RoundingMode::ToOdd))
+    }
+
+    /// Take the operand exactly. The operations are rounded to odd with two more digits than
+    /// the context, so that the result can be rounded again in [Self::finish] without double
+    /// rounding, even if it's subnormal. They are evaluated in [Self::nested], so that they are
+    /// not limited to the thread context.
+    #[inline]
+    fn operand<const E: usize, const R: u8>(&self, x: &FloatRepr<E, R>) -> FloatRepr<E, R> {
+        FloatRepr { precision: self.precision + 2, ..x.clone() }
+    }
+
+    /// Set the flag of the signal, and return it as an error if it's trapped
+    fn raise(&self, signal: Signal) -> Result<(), Signal> {
+        let mut flags = self.flags.get();
+        flags.insert(signal);
+        self.flags.set(flags);
+        if self.traps.contains(signal) {
+            Err(signal)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Evaluate a function that is accurate to one unit in the last place of the given precision,
+    /// and increase the precision until the bounds of the error round to the same result.
+    /// The result must not be exact, which is checked by the callers.
+    fn finish_transcendental<const E: usize, const R: u8>(&self, f: impl Fn(usize) -> FloatRepr<E, R>)
+        -> Result<FloatRepr<E, R>, Signal> {
+        let mut wp = self.precision + 8;
+        loop {
+            let value = Self::nested(|| f(wp));
+            let ulp = value.ulp();
+            let mode = self.rounding as u8;
+            let lower = self.round_digits(value.sub_exact(&ulp), self.precision, mode).value();
+            let upper = self.round_digits(value.add_exact(&ulp), self.precision, mode).value();
+            if lower == upper {
+                return self.finish_value(value, true);
+            }
+            wp += wp / 2;
+        }
+    }
+
+    /// Round the number to the precision with the rounding mode, subnormal numbers are rounded at `emin - precision + 1`
+    fn round_digits<const E: usize, const R: u8>(&self, value: FloatRepr<E, R>, precision: usize, mode: u8)
+        -> Approximation<FloatRepr<E, R>, RoundingDirection> {
+        if value.mantissa != ibig!(0) && value.log_radix_floor() < self.emin {
+            value.with_exponent_mode_approx(self.emin - precision as isize + 1, mode)
+        } else {
+            value.with_precision_mode_approx(precision, mode)
+        }
+    }
+
+    /// Round the result to the context, check the exponent range and signal the conditions
+    #[inline]
+    fn finish<const E: usize, const R: u8>(&self, result: Approximation<FloatRepr<E, R>, RoundingDirection>)
+        -> Result<FloatRepr<E, R>, Signal> {
+        let inexact = !result.is_exact();
+        self.finish_value(result.value(), inexact)
+    }
+
+    /// Round the value to the context (see [Self::finish]), `inexact` tells whether the value is already rounded
+    #[inline]
+    fn finish_value<const E: usize, const R: u8>(&self, value: FloatRepr<E, R>, inexact: bool)
+        -> Result<FloatRepr<E, R>, Signal> {
+        self.limit(value, inexact, self.precision, self.rounding as u8).map(Approximation::value)
+    }
+
+    /// Round the value to the precision with the rounding mode, check the exponent range and signal the
+    /// conditions, `inexact` tells whether the value is already rounded. The returned approximation
+    /// only reports the rounding done here.
+    fn limit<const E: usize, const R: u8>(&self, value: FloatRepr<E, R>, inexact: bool, precision: usize, mode: u8)
+        -> Result<Approximation<FloatRepr<E, R>, RoundingDirection>, Signal> {
+        let subnormal = value.mantissa != ibig!(0) && value.log_radix_floor() < self.emin;
+        let mut rounded = self.round_digits(value, precision, mode);
+
+        let value = rounded.value_ref();
+        if value.mantissa != ibig!(0) && value.log_radix_floor() > self.emax {
+            self.raise(Signal::Overflow)?;
+            let mut max = ibig!(1);
+            shl_radix::<E>(&mut max, precision);
+            max -= 1u8;
+            let max = FloatRepr::from_parts(max, self.emax + 1 - precision as isize);
+            rounded = if value.mantissa < ibig!(0) {
+                Approximation::Inexact(-max, RoundingDirection::Up)
+            } else {
+                Approximation::Inexact(max, RoundingDirection::Down)
+            };
+        }
+        let inexact = inexact || !rounded.is_exact();
+        if subnormal && inexact {
+            self.raise(Signal::Underflow)?;
+        }
+        if inexact {
+            self.raise(Signal::Inexact)?;
+        }
+        Ok(rounded.map(|mut value| {
+            value.precision = precision;
+            value
+        }))
+    }
+}
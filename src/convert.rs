@@ -1,30 +1,33 @@
+use core::cmp::Ordering;
 use std::convert::TryInto;
-use ibig::{IBig, ibig, UBig, ops::DivRem};
+use ibig::{IBig, ibig, UBig, ubig, ops::{Abs, DivRem}};
 use crate::{
-    repr::{FloatRepr, BinaryRepr, DecimalRepr},
-    utils::{shr_radix, round_with_rem, get_precision},
-    ibig_ext::{remove_pow, log_rem, log_pow}
+    repr::{FloatRepr, BinaryRepr, DecimalRepr, Rounding, RoundingMode},
+    approx::{Approximation, RoundingDirection},
+    error::Error,
+    utils::{shl_radix, shr_radix, shr_rem_radix, shr_round_radix, round_with_fract, get_precision, perfect_power},
+    ibig_ext::{remove_pow, log_pow, log_pow_rem, magnitude, PowerTree}
 };
 
 impl<const R: u8> From<f32> for BinaryRepr<R> {
     fn from(f: f32) -> Self {
         let bits: u32 = f.to_bits();
 
-        let mut exponent: isize = ((bits >> 23) & 0xff) as isize;
-        exponent -= 127 + 23; // bias + mantissa shift
-
-        let mantissa = if exponent == 0 {
-            (bits & 0x7fffff) << 1
+        let biased: isize = ((bits >> 23) & 0xff) as isize;
+        let (mantissa, exponent) = if biased == 0 {
+            // subnormal numbers
+            (bits & 0x7fffff, 1 - (127 + 23))
         } else {
-            (bits & 0x7fffff) | 0x800000
-        } as i32;
+            ((bits & 0x7fffff) | 0x800000, biased - (127 + 23)) // bias + mantissa shift
+        };
+        let mantissa = mantissa as i32;
         let mantissa = if bits >> 31 == 0 {
             IBig::from(mantissa)
         } else {
             IBig::from(-mantissa)
         };
 
-        Self { mantissa, exponent, precision: 24 }
+        Self::from_parts_with_precision(mantissa, exponent, 24)
     }
 }
 
@@ -32,21 +35,21 @@ impl<const R: u8> From<f64> for BinaryRepr<R> {
     fn from(f: f64) -> Self {
         let bits: u64 = f.to_bits();
 
-        let mut exponent: isize = ((bits >> 52) & 0x7ff) as isize;
-        exponent -= 1023 + 52; // bias + mantissa shift
-
-        let mantissa = if exponent == 0 {
-            (bits & 0xfffffffffffff) << 1
+        let biased: isize = ((bits >> 52) & 0x7ff) as isize;
+        let (mantissa, exponent) = if biased == 0 {
+            // subnormal numbers
+            (bits & 0xfffffffffffff, 1 - (1023 + 52))
         } else {
-            (bits & 0xfffffffffffff) | 0x10000000000000
-        } as i64;
+            ((bits & 0xfffffffffffff) | 0x10000000000000, biased - (1023 + 52)) // bias + mantissa shift
+        };
+        let mantissa = mantissa as i64;
         let mantissa = if bits >> 63 == 0 {
             IBig::from(mantissa)
         } else {
             IBig::from(-mantissa)
         };
 
-        Self { mantissa, exponent, precision: 53 }
+        Self::from_parts_with_precision(mantissa, exponent, 53)
     }
 }
 
@@ -57,26 +60,68 @@ impl<const E: usize, const R: u8> FloatRepr<E, R> {
     }
 
     /// Create a floating number by dividing two integers with given precision
+    /// 
+    /// # Panics
+    /// If the denominator is zero
+    #[inline]
     pub fn from_ratio(numerator: IBig, denominator: IBig, precision: usize) -> Self {
+        Self::from_ratio_mode_approx(numerator, denominator, precision, R).value()
+    }
+
+    /// Create a floating number by dividing two integers, rounded with a mode
+    /// from [RoundingMode](crate::RoundingMode)
+    /// 
+    /// # Panics
+    /// If the denominator is zero
+    pub(crate) fn from_ratio_mode_approx(numerator: IBig, denominator: IBig, precision: usize, mode: u8)
+        -> Approximation<Self, RoundingDirection> {
         // FIXME: investigate whether it's faster to first calculate the inverse of denom, and then multiply
-        // FIXME: find a way to use the fast div support from ibig
-        let (mut mantissa, mut rem) = numerator.div_rem(&denominator);
-        let mut digits = get_precision::<E>(&mantissa);
-        let mut exponent = 0;
-        if digits < precision {
-            while digits < precision && &rem != &ibig!(0) {
-                let (d, r) = (rem * E).div_rem(&denominator);
-                rem = r;
-                mantissa *= E;
-                mantissa += d;
-                digits += 1;
-                exponent -= 1;
-            }
+        assert!(denominator != ibig!(0), "division by zero");
+        if numerator == ibig!(0) {
+            return Approximation::Exact(Self { precision, ..Self::from_parts(numerator, 0) });
+        }
+
+        // scale the numerator so that the quotient has at least precision digits
+        let num_digits = get_precision::<E>(&numerator);
+        let den_digits = get_precision::<E>(&denominator);
+        let shift = (precision + den_digits).saturating_sub(num_digits);
+        let mut numerator = numerator;
+        shl_radix::<E>(&mut numerator, shift);
+        let den_magnitude = magnitude(&denominator);
+        let (quotient, rem) = if den_magnitude.is_power_of_two() {
+            // dividing by a power of two is a shift, which is common in the conversions from binary
+            let (quotient, rem) = shr_rem_radix::<2>(&numerator, den_magnitude.bit_len() - 1);
+            (if denominator < ibig!(0) { -quotient } else { quotient }, rem)
+        } else {
+            numerator.div_rem(&denominator)
+        };
+        let mut exponent = -(shift as isize);
+
+        // remove the extra digits of the quotient, which are then a part of the remainder
+        let extra = get_precision::<E>(&quotient).saturating_sub(precision);
+        let (trunc, rem, den) = if extra > 0 {
+            let (trunc, low) = shr_rem_radix::<E>(&quotient, extra);
+            let mut unit = denominator.clone();
+            shl_radix::<E>(&mut unit, extra);
+            exponent += extra as isize;
+            (trunc, low * &denominator + rem, unit)
         } else {
-            shr_radix::<E>(&mut mantissa, digits - precision);
-            exponent = (digits - precision) as isize;
+            (quotient, rem, denominator)
+        };
+        if rem == ibig!(0) {
+            return Approximation::Exact(Self { precision, ..Self::from_parts(trunc, exponent) });
         }
-        Self::from_parts_with_precision(mantissa, exponent, precision)
+
+        // the remainder has the sign of the numerator, and the quotient is truncated toward zero
+        let rem = if den < ibig!(0) { -rem } else { rem };
+        let mut mantissa = trunc.clone();
+        round_with_fract(&mut mantissa, &rem, &den, mode);
+        let direction = if (mantissa != trunc) == (rem > ibig!(0)) {
+            RoundingDirection::Up
+        } else {
+            RoundingDirection::Down
+        };
+        Approximation::Inexact(Self { precision, ..Self::from_parts(mantissa, exponent) }, direction)
     }
 
     /// Convert the float number to decimal based exponents.
@@ -111,24 +156,121 @@ impl<const E: usize, const R: u8> FloatRepr<E, R> {
     /// 
     /// If the given precision is less than the previous value,
     /// it will be rounded following the rounding mode specified by the type parameter.
+    #[inline]
     pub fn with_precision(self, precision: usize) -> Self {
-        let mut result = self;
+        self.with_precision_mode(precision, R)
+    }
+
+    /// Explicitly change the precision of the number, with a rounding mode selected at runtime.
+    /// 
+    /// If the given precision is less than the previous value, it will be rounded following
+    /// the given rounding mode instead of the one specified by the type parameter.
+    #[inline]
+    pub fn with_precision_rounded(self, precision: usize, rounding: Rounding) -> Self {
+        self.with_precision_mode(precision, rounding as u8)
+    }
+
+    /// Explicitly change the precision of the number, and report whether the
+    /// result is exact and the direction of the rounding if it's not.
+    #[inline]
+    pub fn with_precision_approx(self, precision: usize) -> Approximation<Self, RoundingDirection> {
+        self.with_precision_mode_approx(precision, R)
+    }
 
+    /// Change the precision of the number with a rounding mode from [RoundingMode](crate::RoundingMode)
+    #[inline]
+    pub(crate) fn with_precision_mode(self, precision: usize, mode: u8) -> Self {
+        self.with_precision_mode_approx(precision, mode).value()
+    }
+
+    pub(crate) fn with_precision_mode_approx(self, precision: usize, mode: u8) -> Approximation<Self, RoundingDirection> {
         // shrink if possible
-        if result.precision > precision {
-            let actual = result.actual_precision();
-            if actual > precision {
-                let shift = actual - precision;
-                shr_radix::<E>(&mut result.mantissa, shift - 1); // left one additional digit for rounding
-                let (mut man, rem) = result.mantissa.div_rem(E as isize);
-                round_with_rem::<E, R>(&mut man, rem);
-                result.mantissa = man;
-                result.exponent += shift as isize;
-            }
+        let actual = self.actual_precision();
+        if actual <= precision {
+            return Approximation::Exact(Self { precision, ..self });
         }
 
-        result.precision = precision;
-        return result;
+        let exponent = self.exponent + (actual - precision) as isize;
+        self.with_exponent_mode_approx(exponent, mode).map(|value| Self { precision, ..value })
+    }
+
+    /// Round the number so that its lowest digit is not below `radix^exponent`,
+    /// the precision is kept unchanged
+    pub(crate) fn with_exponent_mode_approx(self, exponent: isize, mode: u8) -> Approximation<Self, RoundingDirection> {
+        if self.mantissa == ibig!(0) || self.exponent >= exponent {
+            return Approximation::Exact(self);
+        }
+
+        // a number far below the unit is replaced by a sticky digit, which rounds the same way
+        let precision = self.precision;
+        let top = self.exponent + self.actual_precision() as isize;
+        let (value, shift) = if top < exponent - 1 {
+            (self.mantissa.signum(), 2)
+        } else {
+            (self.mantissa, (exponent - self.exponent) as usize)
+        };
+
+        let (trunc, rem) = shr_rem_radix::<E>(&value, shift);
+        if rem == ibig!(0) {
+            return Approximation::Exact(Self { precision, ..Self::from_parts(trunc, exponent) });
+        }
+
+        let mut unit = ibig!(1);
+        shl_radix::<E>(&mut unit, shift);
+        let mut mantissa = trunc.clone();
+        round_with_fract(&mut mantissa, &rem, &unit, mode);
+
+        // the mantissa is either truncated toward zero, or moved away from zero by one
+        let direction = if (mantissa != trunc) == (rem > ibig!(0)) {
+            RoundingDirection::Up
+        } else {
+            RoundingDirection::Down
+        };
+
+        // the rounding could produce trailing zeros
+        let result = Self { precision, ..Self::from_parts(mantissa, exponent) };
+        Approximation::Inexact(result, direction)
+    }
+
+    /// Change the precision of the number to hold at least `bits` binary digits,
+    /// i.e. the smallest precision p such that `radix^p >= 2^bits`
+    #[inline]
+    pub fn with_bits(self, bits: usize) -> Self {
+        self.with_precision(Self::precision_from_digits(2, bits))
+    }
+
+    /// Change the precision of the number to hold at least `digits` decimal digits,
+    /// i.e. the smallest precision p such that `radix^p >= 10^digits`
+    #[inline]
+    pub fn with_decimal_digits(self, digits: usize) -> Self {
+        self.with_precision(Self::precision_from_digits(10, digits))
+    }
+
+    /// Get the number of binary digits that the precision can hold,
+    /// i.e. the largest k such that `2^k <= radix^precision`
+    #[inline]
+    pub fn precision_bits(&self) -> usize {
+        log_pow(&UBig::from(E), self.precision, 2)
+    }
+
+    /// Get the number of decimal digits that the precision can hold,
+    /// i.e. the largest k such that `10^k <= radix^precision`
+    #[inline]
+    pub fn precision_decimal_digits(&self) -> usize {
+        log_pow(&UBig::from(E), self.precision, 10)
+    }
+
+    /// Get the smallest precision p such that `radix^p >= base^digits`
+    fn precision_from_digits(base: usize, digits: usize) -> usize {
+        if base == E {
+            return digits;
+        }
+        let (precision, rem) = log_pow_rem(&UBig::from(base), digits, E);
+        if rem == ubig!(0) {
+            precision
+        } else {
+            precision + 1
+        }
     }
 
     /// Explicitly change the rounding mode of the number.
@@ -144,70 +286,182 @@ impl<const E: usize, const R: u8> FloatRepr<E, R> {
     /// 
     /// The precision of the result number will be at most equal to the
     /// precision of the original number (numerically), that is
-    /// ```new_radix ^ new_precision <= old_radix ^ old_precision```.
+    /// ```new_radix ^ new_precision <= old_radix ^ old_precision```, except that at least
+    /// one digit is kept in the new radix.
     /// If any rounding happens during the conversion, if will follow
     /// the rounding mode specified by the type parameter.
     #[allow(non_upper_case_globals)]
+    #[inline]
     pub fn with_radix<const NewE: usize>(self) -> FloatRepr<NewE, R> {
-        if NewE == E {
-            return FloatRepr { mantissa: self.mantissa, exponent: self.exponent, precision: self.precision };
+        self.with_radix_approx::<NewE>().value()
+    }
+
+    /// Explicitly change the radix of the float number (see [Self::with_radix]),
+    /// returning an error instead of panicking if the radix is not supported
+    #[allow(non_upper_case_globals)]
+    pub fn checked_with_radix<const NewE: usize>(self) -> Result<FloatRepr<NewE, R>, Error> {
+        if NewE < 2 || NewE > isize::MAX as usize {
+            return Err(Error::InvalidRadix);
         }
-        // FIXME: shortcut if E is a power of NewE
+        Ok(self.with_radix())
+    }
 
+    /// Explicitly change the radix of the float number (see [Self::with_radix]), and
+    /// report whether the result is exact and the direction of the rounding if it's not.
+    #[allow(non_upper_case_globals)]
+    pub fn with_radix_approx<const NewE: usize>(self) -> Approximation<FloatRepr<NewE, R>, RoundingDirection> {
+        if NewE == E {
+            return Approximation::Exact(FloatRepr { mantissa: self.mantissa, exponent: self.exponent, precision: self.precision });
+        }
         // Calculate the new precision
-        // new_precision = floor_log_radix2(radix1^precision)
-        let precision = log_pow(&UBig::from(E), self.precision, NewE);
+        // new_precision = floor_log_radix2(radix1^precision), but at least one digit is kept
+        let precision = match self.precision {
+            0 => 0,
+            p => log_pow(&UBig::from(E), p, NewE).max(1)
+        };
+        self.with_radix_mode_approx::<NewE>(precision, R)
+    }
 
-        // Convert by calculating logarithm
-        // FIXME: currently the calculation is done in full precision, could be vastly optimized
-        let result = if self.exponent == 0 {
+    /// Change the radix of the float number, and round the result to the given precision
+    /// with a rounding mode from [RoundingMode](crate::RoundingMode).
+    #[allow(non_upper_case_globals)]
+    pub(crate) fn with_radix_mode_approx<const NewE: usize>(self, precision: usize, mode: u8)
+        -> Approximation<FloatRepr<NewE, R>, RoundingDirection> {
+        if NewE == E || self.exponent == 0 {
             // direct copy if the exponent is zero
-            return FloatRepr { mantissa: self.mantissa, exponent: 0, precision };
-        } else if self.exponent > 0 {
-            // denote log with base of radix2 as lgr2, then
-            // mantissa * radix1 ^ exp1
-            // = mantissa * radix2 ^ lgr2(radix1^exp1)
-            // = mantissa * (radix2 ^ floor_lgr2(radix1^exp1) + rem_lgr2(radix1^exp1))
-            // = mantissa * ratio * (radix2 ^ floor_lgr2(radix1^exp1))
-            // where the ratio is
-            // 1 + rem_lgr2(radix1^exp1) / (radix2 ^ floor_lgr2(radix1^exp1))
-            // = radix1^exp1 / (radix1^exp1 - rem_lgr2(radix1^exp1))
-
-            let precision_ub = UBig::from(E).pow(self.exponent as usize);
-            let (log_v, log_r) = log_rem(&precision_ub, NewE);
-            let den = IBig::from(&precision_ub - log_r);
-            let num = IBig::from(precision_ub) * self.mantissa;
-            let mut value = FloatRepr::<NewE, R>::from_ratio(num, den, precision + 1);
-            value.exponent += log_v as isize;
-            value
+            return FloatRepr::<NewE, R>::from_parts(self.mantissa, self.exponent).with_precision_mode_approx(precision, mode);
+        }
+
+        // if the radices are powers of a common base, the conversion is exact by regrouping the digits:
+        // mantissa * base^(a*exp) = (mantissa * base^r) * (base^c)^q, where a*exp = c*q + r
+        let (base, a) = perfect_power(E);
+        let (new_base, c) = perfect_power(NewE);
+        if base == new_base {
+            let exponent = self.exponent.checked_mul(a as isize).expect("exponent overflow");
+            let q = exponent.div_euclid(c as isize);
+            let r = exponent.rem_euclid(c as isize) as usize;
+            let mantissa = self.mantissa * IBig::from(base).pow(r);
+            return FloatRepr::<NewE, R>::from_parts(mantissa, q).with_precision_mode_approx(precision, mode);
+        }
+
+        // Convert with bounds of the power of the old radix that are truncated to a working precision
+        // slightly above the target precision, and increase the working precision if the bounds are too
+        // far apart to decide the rounding. The exact conversion is used once the power is not longer
+        // than the working precision, so the cost depends on the precision instead of the exponent.
+        let n = self.exponent.unsigned_abs();
+        let power_digits = n.saturating_mul(E.ilog2() as usize) / ((NewE - 1).ilog2() as usize + 1);
+        let mut digits = precision + (usize::BITS - n.leading_zeros()) as usize + 2;
+        while precision != 0 && 2 * digits < power_digits {
+            if let Some(result) = self.try_with_radix_mode::<NewE>(digits, precision, mode) {
+                return result;
+            }
+            digits *= 2;
+        }
+
+        // Convert exactly, the integer or the quotient is rounded once at the end
+        let power = IBig::from(PowerTree::new(E).pow(n));
+        if self.exponent > 0 {
+            FloatRepr::<NewE, R>::from_parts(self.mantissa * power, 0).with_precision_mode_approx(precision, mode)
         } else {
-            // denote log with base of radix2 as lgr2, then
-            // mantissa / radix1 ^ exp1
-            // = mantissa / radix2 ^ lgr2(radix1^exp1)
-            // = mantissa / (radix2 ^ floor_lgr2(radix1^exp1) + rem_lgr2(radix1^exp1))
-            // = mantissa (1 / (radix2 ^ floor_lgr2(..)) - rem_lgr2(..) / (radix2 ^ floor_lgr2(..) * (radix2 ^ floor_lgr2(..) + rem_lgr2(..)))
-            // = mantissa * ratio * (1 / (radix2 ^ floor_lgr2(radix1^exp1))
-            // where the ratio is
-            // 1 - rem_lgr2(radix1^exp1) / (radix2 ^ floor_lgr2(radix1^exp1) + rem_lgr2(radix1^exp1))
-            // = radix2 ^ floor_lgr2(radix1^exp1) / radix1^exp1
-
-            let precision_ub = UBig::from(E).pow(-self.exponent as usize);
-            let (log_v, log_r) = log_rem(&precision_ub, NewE);
-            let num = IBig::from(&precision_ub - log_r) * self.mantissa;
-            let den = IBig::from(precision_ub);
-            let mut value = FloatRepr::<NewE, R>::from_ratio(num, den, precision + 1);
-            value.exponent -= log_v as isize;
-            value
+            FloatRepr::<NewE, R>::from_ratio_mode_approx(self.mantissa, power, precision, mode)
+        }
+    }
+
+    /// Try converting the number to the new radix with the bounds of the power of the old radix
+    /// truncated to the given digits (see [Self::radix_pow_bounds]). It returns None if the result
+    /// can't be decided from the bounds.
+    #[allow(non_upper_case_globals)]
+    fn try_with_radix_mode<const NewE: usize>(&self, digits: usize, precision: usize, mode: u8)
+        -> Option<Approximation<FloatRepr<NewE, R>, RoundingDirection>> {
+        let (power_lo, power_hi, power_exp) = Self::radix_pow_bounds::<NewE>(self.exponent.unsigned_abs(), digits);
+        if power_lo == ibig!(0) {
+            return None;
+        }
+
+        // the magnitude is bounded by lo * NewE^exponent <= |x| <= hi * NewE^exponent
+        let magnitude = (&self.mantissa).abs();
+        let (lo, hi, exponent) = if self.exponent > 0 {
+            (&magnitude * power_lo, magnitude * power_hi, power_exp)
+        } else {
+            // make sure that the quotient has at least the digits of the power
+            let shift = (2 * digits).saturating_sub(get_precision::<NewE>(&magnitude));
+            let mut num = magnitude;
+            shl_radix::<NewE>(&mut num, shift);
+            let lo = &num / power_hi;
+            let hi = num / power_lo + 1u8;
+            (lo, hi, -(shift as isize) - power_exp)
+        };
+        let (lo, hi) = if self.mantissa < ibig!(0) { (-hi, -lo) } else { (lo, hi) };
+
+        // the rounding is decided if both bounds are rounded to the same value outside of the bounds
+        let lo = FloatRepr::<NewE, R>::from_parts(lo, exponent);
+        let hi = FloatRepr::<NewE, R>::from_parts(hi, exponent);
+        let result = lo.clone().with_precision_mode_approx(precision, mode).value();
+        if result != hi.clone().with_precision_mode_approx(precision, mode).value() {
+            None
+        } else if result < lo {
+            Some(Approximation::Inexact(result, RoundingDirection::Down))
+        } else if result > hi {
+            Some(Approximation::Inexact(result, RoundingDirection::Up))
+        } else {
+            None
+        }
+    }
+
+    /// Bound `E^n` in the radix `NewE` with at most the given digits, it returns (lo, hi, e) such that
+    /// `lo * NewE^e <= E^n <= hi * NewE^e`. The powers `E^(2^k)` are squared and multiplied for the
+    /// bits of n like [PowerTree], where the lower bounds are truncated down and the upper bounds are
+    /// truncated up, so the bounds hold without an analysis of the accumulated error.
+    #[allow(non_upper_case_globals)]
+    fn radix_pow_bounds<const NewE: usize>(n: usize, digits: usize) -> (IBig, IBig, isize) {
+        // both bounds are truncated by the same digits, so that they share the exponent
+        let truncate = |lo: IBig, hi: IBig, exponent: isize| {
+            let len = get_precision::<NewE>(&hi);
+            if len <= digits {
+                return (lo, hi, exponent);
+            }
+            let shift = len - digits;
+            let mut lo = lo;
+            shr_radix::<NewE>(&mut lo, shift);
+            let (mut hi, rem) = shr_rem_radix::<NewE>(&hi, shift);
+            if rem != ibig!(0) {
+                hi += 1u8;
+            }
+            (lo, hi, exponent + shift as isize)
         };
 
-        result.with_precision(precision)
+        let mut square = (IBig::from(E), IBig::from(E), 0isize);
+        let mut result = (ibig!(1), ibig!(1), 0isize);
+        for k in 0..usize::BITS - n.leading_zeros() {
+            if k > 0 {
+                let exponent = square.2.checked_mul(2).expect("exponent overflow");
+                square = truncate(&square.0 * &square.0, &square.1 * &square.1, exponent);
+            }
+            if (n >> k) & 1 == 1 {
+                result = truncate(&result.0 * &square.0, &result.1 * &square.1, result.2 + square.2);
+            }
+        }
+        result
+    }
+
+    /// Explicitly change the radix of the float number, and round the result to
+    /// the given precision (in the new radix) with the rounding mode of the type.
+    ///
+    /// The conversion is exact before rounding, so the result is correctly rounded.
+    #[allow(non_upper_case_globals)]
+    #[inline]
+    pub fn with_radix_and_precision<const NewE: usize>(self, precision: usize) -> FloatRepr<NewE, R> {
+        self.with_radix_mode_approx::<NewE>(precision, R).value()
     }
 
+    /// Explicitly change the radix of the float number and round it to the given precision
+    /// (see [Self::with_radix_and_precision]), and report whether the result is exact
+    /// and the direction of the rounding if it's not.
     #[allow(non_upper_case_globals)]
-    fn with_radix_and_precision<const NewE: usize>(self, precision: usize) -> FloatRepr<NewE, R> {
-        // approximate power if precision is small
-        // calculate more digits if precision is high
-        unimplemented!()
+    #[inline]
+    pub fn with_radix_and_precision_approx<const NewE: usize>(self, precision: usize)
+        -> Approximation<FloatRepr<NewE, R>, RoundingDirection> {
+        self.with_radix_mode_approx::<NewE>(precision, R)
     }
 
     /// Convert raw parts into a float number, the precision will be inferred from mantissa
@@ -218,13 +472,16 @@ impl<const E: usize, const R: u8> FloatRepr<E, R> {
     #[inline]
     pub fn from_parts(mut mantissa: IBig, mut exponent: isize) -> Self {
         // TODO: prevent using this function internally because we enforce normalized representation
+        if mantissa == ibig!(0) {
+            return Self { mantissa, exponent: 0, precision: 0 };
+        }
         if E == 2 {
             if let Some(shift) = mantissa.trailing_zeros() {
                 mantissa >>= shift;
                 exponent += shift as isize;
             };
         } else {
-            let shift: isize = remove_pow(&mut mantissa, &E.into()).try_into().unwrap();
+            let shift: isize = remove_pow(&mut mantissa, E).try_into().unwrap();
             exponent += shift;
         }
 
@@ -244,20 +501,105 @@ impl<const E: usize, const R: u8> FloatRepr<E, R> {
         (self.mantissa, self.exponent)
     }
 
-    // TODO: let all these to_* functions return `Approximation`
-
     /// Convert the float number to native [f32] with the given rounding mode.
-    fn to_f32(&self) -> f32 {
-        unimplemented!()
+    /// 
+    /// The number is rounded once to the nearest representable values, including the subnormals.
+    /// If it's too large, the result is infinite or the largest finite [f32],
+    /// depending on the rounding direction.
+    pub fn to_f32(&self) -> f32 {
+        f32::from_bits(self.to_ieee_bits(24, 8) as u32)
+    }
+
+    /// Convert the float number to native [f32] (see [Self::to_f32]), and report
+    /// whether the result is exact and the direction of the rounding if it's not.
+    pub fn to_f32_approx(&self) -> Approximation<f32, RoundingDirection> {
+        let value = self.to_f32();
+        self.approx_with(value as f64).map(|_| value)
+    }
+
+    /// Convert the float number to native [f64] with the given rounding mode (see [Self::to_f32]).
+    pub fn to_f64(&self) -> f64 {
+        f64::from_bits(self.to_ieee_bits(53, 11))
+    }
+
+    /// Convert the float number to native [f64] (see [Self::to_f64]), and report
+    /// whether the result is exact and the direction of the rounding if it's not.
+    pub fn to_f64_approx(&self) -> Approximation<f64, RoundingDirection> {
+        self.approx_with(self.to_f64())
     }
 
-    /// Convert the float number to native [f64] with the given rounding mode.
-    fn to_f64(&self) -> f64 {
-        unimplemented!()
+    /// Compare the converted value with the number itself
+    fn approx_with<T: PartialOrd<Self>>(&self, value: T) -> Approximation<T, RoundingDirection> {
+        match value.partial_cmp(self) {
+            Some(Ordering::Greater) => Approximation::Inexact(value, RoundingDirection::Up),
+            Some(Ordering::Less) => Approximation::Inexact(value, RoundingDirection::Down),
+            _ => Approximation::Exact(value)
+        }
+    }
+
+    /// Encode the number in the IEEE 754 binary interchange format with `bits` digits of
+    /// precision (including the implicit bit) and an exponent field of `width` bits.
+    fn to_ieee_bits(&self, bits: usize, width: usize) -> u64 {
+        if self.mantissa == ibig!(0) {
+            return 0;
+        }
+        let negative = self.mantissa < ibig!(0);
+        let sign = (negative as u64) << (bits - 1 + width);
+
+        let emax = (1isize << (width - 1)) - 1;
+        let emin = 1 - emax;
+        // the exponent of the lowest digit of the smallest subnormal number
+        let lowest = emin - (bits - 1) as isize;
+
+        // rounding to odd with two more digits keeps the final rounding correct
+        let binary = self.clone().with_radix_mode_approx::<2>(bits + 2, RoundingMode::ToOdd).value();
+        let exponent = (binary.log_radix_floor() - (bits - 1) as isize).max(lowest);
+        let rounded = binary.with_exponent_mode_approx(exponent, R).value();
+        if rounded.mantissa == ibig!(0) {
+            return sign;
+        }
+
+        let fraction_mask = (1u64 << (bits - 1)) - 1;
+        let top = rounded.log_radix_floor();
+        if top > emax {
+            // overflow to infinity, unless the rounding is toward zero
+            let to_infinity = match R {
+                RoundingMode::Zero | RoundingMode::ToOdd => false,
+                RoundingMode::Up => !negative,
+                RoundingMode::Down => negative,
+                _ => true,
+            };
+            return if to_infinity {
+                sign | ((2 * emax as u64 + 1) << (bits - 1))
+            } else {
+                sign | ((2 * emax as u64) << (bits - 1)) | fraction_mask
+            };
+        }
+
+        let mantissa: u64 = magnitude(&rounded.mantissa).try_into().unwrap();
+        if top >= emin {
+            // shift the leading one to the implicit bit
+            let full = mantissa << (bits - 1 - (top - rounded.exponent) as usize);
+            sign | (((top + emax) as u64) << (bits - 1)) | (full & fraction_mask)
+        } else {
+            sign | (mantissa << (rounded.exponent - lowest))
+        }
     }
 
     /// Convert the float number to integer with the given rounding mode.
-    fn to_int(&self) -> IBig {
-        unimplemented!()
+    pub fn to_int(&self) -> IBig {
+        if self.exponent >= 0 {
+            let mut int = self.mantissa.clone();
+            shl_radix::<E>(&mut int, self.exponent as usize);
+            int
+        } else {
+            shr_round_radix::<E>(&self.mantissa, self.exponent.unsigned_abs(), R)
+        }
+    }
+
+    /// Convert the float number to integer (see [Self::to_int]), and report
+    /// whether the result is exact and the direction of the rounding if it's not.
+    pub fn to_int_approx(&self) -> Approximation<IBig, RoundingDirection> {
+        self.approx_with(self.to_int())
     }
 }
@@ -1,70 +1,146 @@
 use core::ops::Div;
-use std::convert::TryInto;
-use ibig::{IBig, ibig, ops::Abs};
-use crate::{repr::FloatRepr, utils::{shr_rem_radix, get_precision}, ibig_ext::log_rem};
+use ibig::{IBig, ibig};
+use crate::{
+    repr::{FloatRepr, Rounding},
+    approx::{Approximation, RoundingDirection},
+    context::{Context, Signal},
+    error::Error,
+    utils::shl_radix
+};
 
 impl<const E: usize, const R: u8> FloatRepr<E, R> {
     /// Calculate the multiplicative inverse (1/x)
+    /// 
+    /// # Panics
+    /// If the number is zero
+    #[inline]
     pub fn recip(&self) -> Self {
-        // FIXME: specialize the case of binary float
-        // FIXME: normalize self to close to 1 for better convergence
+        Self::from_integer(ibig!(1), self.precision).div_with_mode(self.clone(), R).value()
+    }
+
+    /// Divide two numbers, and round the quotient with a rounding mode selected at runtime
+    /// 
+    /// # Panics
+    /// If the divisor is zero
+    #[inline]
+    pub fn div_rounded(&self, rhs: &Self, rounding: Rounding) -> Self {
+        self.clone().div_with_mode(rhs.clone(), rounding as u8).value()
+    }
+
+    /// Divide two numbers, and report whether the quotient is exact and
+    /// the direction of the rounding if it's not.
+    /// 
+    /// # Panics
+    /// If the divisor is zero
+    #[inline]
+    pub fn div_approx(&self, rhs: &Self) -> Approximation<Self, RoundingDirection> {
+        self.clone().div_with_mode(rhs.clone(), R)
+    }
+
+    /// Calculate the multiplicative inverse (1/x), returning an error instead of panicking
+    /// if the number is zero or the exponent overflows
+    #[inline]
+    pub fn checked_recip(&self) -> Result<Self, Error> {
+        Self::from_integer(ibig!(1), self.precision).checked_div(self)
+    }
 
-        // first estimate the reciprocal using the first several digits.
-        // 1. find high parts of mantissa that roughly fits in i32
-        // 2. calculate the highest power of radix that fits in i64 (this should be constant)
-        // 3. use the power divided by the high parts of mantissa as a fixed point estimation
-        let (shifts, rem) = log_rem(&(i64::MAX as u64).into(), E); // FIXME: make this step constant
-        let rem: u64 = rem.try_into().unwrap();
-        let pow = i64::MAX - rem as i64; // highest possible power of radix
-        let mantissa_digits = self.actual_precision();
-        if shifts > self.precision + mantissa_digits {
-            // if the quotient already has the desired precision, just use the quotient
-            let est = IBig::from(pow) / &self.mantissa;
-            let est_digits = get_precision::<E>(&est);
-            let result = Self {
-                mantissa: est,
-                exponent: -self.exponent - shifts as isize,
-                precision: est_digits
-            }.with_precision(self.precision);
-            return result;
+    /// Divide two numbers, returning an error instead of panicking
+    /// if the divisor is zero or the exponent overflows
+    pub fn checked_div(&self, rhs: &Self) -> Result<Self, Error> {
+        if rhs.mantissa == ibig!(0) {
+            return Err(Error::DivisionByZero);
         }
+        if self.mantissa != ibig!(0) {
+            // the quotient is calculated with a few more digits than the precision before rounding
+            let below = self.precision.max(rhs.precision) + rhs.actual_precision() + 2;
+            self.exponent.checked_sub(rhs.exponent)
+                .and_then(|shift| shift.checked_sub_unsigned(below))
+                .and_then(|low| low.checked_add_unsigned(below + self.actual_precision()))
+                .ok_or(Error::ExponentOverflow)?;
+        }
+        Ok(self.clone().try_div_with_mode(rhs.clone(), R)?.value())
+    }
 
-        let mut guess = if mantissa_digits > shifts / 2 {
-            let (mantissa_hi, _) = shr_rem_radix::<E>(&self.mantissa, mantissa_digits - shifts / 2);
-            let mantissa_hi: i64 = mantissa_hi.abs().try_into().unwrap();
-            let est = pow / mantissa_hi;
-            Self {
-                mantissa: IBig::from(est),
-                exponent: -self.exponent - ((shifts + 1) / 2) as isize,
-                precision: self.precision + 1 // one more digit for rounding
-            }
-        } else {
-            let mantissa: i64 = self.mantissa.clone().try_into().unwrap();
-            let est = pow / mantissa;
-            Self {
-                mantissa: IBig::from(est),
-                exponent: -self.exponent - shifts as isize,
-                precision: self.precision + 1
-            }
-        };
+    /// Divide two numbers without rounding, the precision of the result is the larger one
+    /// of the two operands, and it's grown if the exact quotient needs more digits.
+    ///
+    /// An [Error::Inexact] is returned if the quotient has infinitely many digits in the radix,
+    /// that is, if the reduced denominator has a prime factor that doesn't divide the radix.
+    pub fn div_exact(&self, rhs: &Self) -> Result<Self, Error> {
+        if rhs.mantissa == ibig!(0) {
+            return Err(Error::DivisionByZero);
+        }
+        let precision = self.precision.max(rhs.precision);
+        if self.mantissa == ibig!(0) {
+            return Ok(Self::from_integer(ibig!(0), precision));
+        }
 
-        // then perform newton interations x = x(2 - a*x)
-        let two = Self::from_integer(ibig!(2), self.precision + 1);
-        loop {
-            let new_guess = &guess * (&two - self * &guess);
-            println!("new_guess: {}, prec: {}", new_guess.to_decimal(), new_guess.precision());
-            if new_guess == guess {
-                break new_guess;
+        // reduce the fraction, and keep the denominator positive
+        let g = self.mantissa.gcd(&rhs.mantissa);
+        let mut num = &self.mantissa / &g;
+        let mut den = &rhs.mantissa / &g;
+        if den < ibig!(0) {
+            num = -num;
+            den = -den;
+        }
+
+        // find the smallest k such that the denominator divides radix^k
+        let radix = IBig::from(E);
+        let mut rest = den.clone();
+        let mut k = 0;
+        while rest != ibig!(1) {
+            let g = rest.gcd(&radix);
+            if g == ibig!(1) {
+                return Err(Error::Inexact);
             }
-            guess = new_guess;
+            rest /= g;
+            k += 1;
         }
+
+        let mut scale = ibig!(1);
+        shl_radix::<E>(&mut scale, k);
+        let mantissa = num * (scale / den);
+        let exponent = self.exponent.checked_sub(rhs.exponent)
+            .and_then(|e| e.checked_sub_unsigned(k))
+            .ok_or(Error::ExponentOverflow)?;
+        Ok(Self::from_parts(mantissa, exponent).with_min_precision(precision))
+    }
+
+    #[inline]
+    pub(crate) fn div_with_mode(self, rhs: Self, mode: u8) -> Approximation<Self, RoundingDirection> {
+        Context::expect_in_range(self.try_div_with_mode(rhs, mode))
+    }
+
+    /// Divide two numbers limited to the thread context (see [Context::bounded])
+    pub(crate) fn try_div_with_mode(self, rhs: Self, mode: u8) -> Result<Approximation<Self, RoundingDirection>, Signal> {
+        let precision = self.precision.max(rhs.precision);
+        Context::bounded(precision, mode, |precision, mode| self.div_to_precision(rhs, precision, mode))
+    }
+
+    fn div_to_precision(self, rhs: Self, precision: usize, mode: u8) -> Approximation<Self, RoundingDirection> {
+        let shift = self.exponent.checked_sub(rhs.exponent).expect("exponent overflow");
+        let result = Self::from_ratio_mode_approx(self.mantissa, rhs.mantissa, precision, mode);
+        result.map(|mut value| {
+            if value.mantissa != ibig!(0) {
+                value.exponent = value.exponent.checked_add(shift).expect("exponent overflow");
+            }
+            value
+        })
     }
 }
 
 impl<const E: usize, const R: u8> Div for FloatRepr<E, R> {
     type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_with_mode(rhs, R).value()
+    }
+}
+
+impl<const E: usize, const R: u8> Div for &FloatRepr<E, R> {
+    type Output = FloatRepr<E, R>;
+    #[inline]
     fn div(self, rhs: Self) -> Self::Output {
-        // TODO: directly divide, refactor with from_ratio
-        self * rhs.recip()
+        self.clone().div(rhs.clone())
     }
 }
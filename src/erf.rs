@@ -0,0 +1,189 @@
+//! Implementation of the error function and its relatives
+
+use ibig::{IBig, ibig};
+use crate::{repr::FloatRepr, context::Context, utils::{get_precision, newton_schedule}};
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the error function `erf(x) = 2/sqrt(pi) * integral_0^x exp(-t^2) dt`
+    pub fn erf(&self) -> Self {
+        Context::bounded_value(|| self.erf_unbounded())
+    }
+
+    fn erf_unbounded(&self) -> Self {
+        let precision = self.precision;
+        if self.mantissa == ibig!(0) {
+            return self.clone();
+        }
+
+        // erfc(x) < exp(-x^2) <= radix^-(precision + 2), so the result is 1 - tiny
+        let x2 = self.square_f64();
+        if x2 > (precision + 2) as f64 * (E as f64).ln() {
+            let one = Self::one_with_tiny(precision, true);
+            let one = if self.mantissa < ibig!(0) { -one } else { one };
+            return one.with_precision(precision);
+        }
+
+        let wp = precision + Self::erf_guard(x2, precision);
+        self.erf_series(wp).with_precision(precision)
+    }
+
+    /// Calculate the complementary error function `erfc(x) = 1 - erf(x)`
+    /// 
+    /// The result is accurate even in the far tail where `1 - erf(x)` cancels.
+    pub fn erfc(&self) -> Self {
+        Context::bounded_value(|| self.erfc_unbounded())
+    }
+
+    fn erfc_unbounded(&self) -> Self {
+        let precision = self.precision;
+        if self.mantissa <= ibig!(0) {
+            // erfc(x) = 1 + erf(-x), there is no cancellation
+            let wp = precision + 2;
+            let erf = (-self).with_precision(wp).erf();
+            return (Self::from_integer(ibig!(1), wp) + erf).with_precision(precision);
+        }
+
+        let ln_e = (E as f64).ln();
+        let x2 = self.square_f64();
+        if x2 > (precision + 5) as f64 * ln_e {
+            // the smallest term of the asymptotic series is about exp(-x^2)
+            return self.erfc_asymptotic(precision);
+        }
+
+        // 1 - erf(x) loses about -log_radix(erfc(x)) = (x^2 + ln(x sqrt(pi))) / ln(radix) digits
+        let cancel = ((x2 + 0.5 * x2.max(1.).ln() + 1.) / ln_e) as usize + 1;
+        let wp = precision + cancel + Self::erf_guard(x2, precision + cancel);
+        let erf = self.erf_series(wp);
+        (Self::from_integer(ibig!(1), wp) - erf).with_precision(precision)
+    }
+
+    /// Calculate the inverse error function, i.e. the number x such that `erf(x) = self`
+    /// 
+    /// # Panics
+    /// If the number is not in the range (-1, 1)
+    pub fn erf_inv(&self) -> Self {
+        Context::bounded_value(|| self.erf_inv_unbounded())
+    }
+
+    fn erf_inv_unbounded(&self) -> Self {
+        let precision = self.precision;
+        if self.mantissa == ibig!(0) {
+            return self.clone();
+        }
+        if self.mantissa < ibig!(0) {
+            return -(-self).erf_inv();
+        }
+
+        // calculate q = 1 - y exactly, note that y < 1 implies a negative exponent
+        let exact = (-self.exponent).max(0) as usize + 1;
+        let q = Self::from_integer(ibig!(1), exact) - self.clone().with_precision(exact);
+        assert!(q.mantissa > ibig!(0), "erf_inv is only defined in (-1, 1)");
+
+        // solve erf(x) = y for small y, and solve erfc(x) = q if y is close to 1 to prevent cancellation
+        let use_erfc = self.ln_f64() > -(2f64.ln());
+        let x0 = if use_erfc {
+            // from the asymptotic expansion erfc(x) ~ exp(-x^2) / (x sqrt(pi))
+            let l = -q.ln_f64();
+            let x0 = (l - (l.sqrt() * core::f64::consts::PI.sqrt()).ln()).max(0.25).sqrt();
+            FloatRepr::<2, R>::from(x0).with_radix::<E>()
+        } else {
+            // from the taylor series erf_inv(y) = sqrt(pi)/2 * (y + pi/12 * y^3 + ...)
+            let y = self.ln_f64().exp();
+            let ratio = core::f64::consts::PI.sqrt() / 2. * (1. + core::f64::consts::PI / 12. * y * y);
+            self * FloatRepr::<2, R>::from(ratio).with_radix::<E>()
+        };
+
+        // newton iterations with doubling precision. since the initial estimation can be rough,
+        // the iteration continues in full precision until the step is negligible. the step is
+        // scaled by exp(x^2) ~ 1/q, so the first iterations need about log_radix(1/q) digits
+        // more than the few digits of x they resolve, otherwise the step overshoots
+        let full = precision + 2;
+        let half_sqrt_pi = Self::pi(full).sqrt() / Self::from_integer(ibig!(2), full);
+        let start = 4 + (-q.ln_f64() / (E as f64).ln()).max(0.) as usize;
+
+        let mut x = x0;
+        let mut schedule = newton_schedule(start.min(full), full).into_iter();
+        let mut wp = schedule.next().unwrap();
+        let mut extra = 0;
+        loop {
+            let x_wp = x.with_precision(wp);
+            let residual = if use_erfc {
+                q.clone().with_precision(wp) - x_wp.erfc()
+            } else {
+                x_wp.erf() - self.clone().with_precision(wp)
+            };
+            let x2 = &x_wp * &x_wp;
+            let step = residual * half_sqrt_pi.clone().with_precision(wp) * x2.exp();
+            x = &x_wp - &step;
+
+            match schedule.next() {
+                Some(next) => wp = next,
+                None if extra < 8 && step.mantissa != ibig!(0)
+                    && step.log_radix_floor() + 1 >= x.log_radix_floor() - precision as isize => extra += 1,
+                None => break
+            }
+        }
+        x.with_precision(precision)
+    }
+
+    /// Estimate x^2 in f64, which is infinite if the number is too large
+    fn square_f64(&self) -> f64 {
+        (2. * self.ln_f64()).exp()
+    }
+
+    /// The guard digits for evaluating [Self::erf_series], which has about `2x^2 + precision * ln(radix)` terms
+    fn erf_guard(x2: f64, precision: usize) -> usize {
+        let terms = 2. * x2 + precision as f64 * (E as f64).ln() + 1.;
+        get_precision::<E>(&IBig::from(terms as usize)) + 2
+    }
+
+    /// Evaluate erf(x) with the given working precision using the series
+    /// `erf(x) = 2x/sqrt(pi) * exp(-x^2) * sum_{n>=0} (2x^2)^n / (1*3*...*(2n+1))`,
+    /// where all the terms are positive.
+    fn erf_series(&self, wp: usize) -> Self {
+        let x = self.clone().with_precision(wp);
+        let x2 = &x * &x;
+        let two_x2 = &x2 + &x2;
+
+        let mut sum = Self::from_integer(ibig!(1), wp);
+        let mut term = sum.clone();
+        let mut n = 1usize;
+        loop {
+            term = &term * &two_x2 / Self::from_integer(IBig::from(2 * n + 1), wp);
+            if term.log_radix_floor() < sum.log_radix_floor() - wp as isize {
+                break;
+            }
+            sum = sum + term.clone();
+            n += 1;
+        }
+
+        let scale = (&x + &x) / Self::pi(wp).sqrt();
+        scale * sum * (-x2).exp()
+    }
+
+    /// Evaluate erfc(x) for large x using the asymptotic series
+    /// `erfc(x) ~ exp(-x^2) / (x sqrt(pi)) * sum_{n>=0} (-1)^n (2n-1)!! / (2x^2)^n`
+    fn erfc_asymptotic(&self, precision: usize) -> Self {
+        let wp = precision + 3;
+        let x = self.clone().with_precision(wp);
+        let x2 = &x * &x;
+        let two_x2 = &x2 + &x2;
+        let x2_f64 = self.square_f64();
+
+        let mut sum = Self::from_integer(ibig!(1), wp);
+        let mut term = sum.clone();
+        let mut n = 1usize;
+        // the series is divergent, so stop before the terms start increasing
+        while ((2 * n - 1) as f64) < 2. * x2_f64 {
+            term = -(&term * Self::from_integer(IBig::from(2 * n - 1), wp)) / two_x2.clone();
+            if term.log_radix_floor() < -(wp as isize) {
+                break;
+            }
+            sum = sum + term.clone();
+            n += 1;
+        }
+
+        let den = x * Self::pi(wp).sqrt();
+        ((-x2).exp() * sum / den).with_precision(precision)
+    }
+}
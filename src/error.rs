@@ -0,0 +1,62 @@
+//! Error type of the checked operations
+
+use core::fmt::{self, Display, Formatter};
+use ibig::error::ParseError;
+use crate::context::Signal;
+
+/// Errors returned by the checked operations (such as [FloatRepr::checked_div](crate::FloatRepr::checked_div)),
+/// which would panic in the unchecked counterparts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A number is divided by zero
+    DivisionByZero,
+    /// The exponent of the result (or an intermediate result) exceeds the range of [isize],
+    /// or the result is out of the exponent range of the thread context (see [Context](crate::Context#thread-context))
+    ExponentOverflow,
+    /// The radix is not supported by the operation
+    InvalidRadix,
+    /// The operand is out of the domain of the function (e.g. the square root of a negative number)
+    Domain,
+    /// The exact result can't be represented with finitely many digits in the radix
+    Inexact,
+    /// The precision is zero, so there are no digits to work with (e.g. for [FloatRepr::checked_ulp](crate::FloatRepr::checked_ulp))
+    ZeroPrecision,
+    /// The string is not a valid number
+    Parse(ParseError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::DivisionByZero => f.write_str("division by zero"),
+            Error::ExponentOverflow => f.write_str("exponent overflow"),
+            Error::InvalidRadix => f.write_str("radix is not supported"),
+            Error::Domain => f.write_str("operand is out of the domain"),
+            Error::Inexact => f.write_str("result is not exactly representable"),
+            Error::ZeroPrecision => f.write_str("precision is zero"),
+            Error::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    #[inline]
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<Signal> for Error {
+    /// Convert a signal trapped by the thread context, overflow and underflow are both out of the exponent range
+    #[inline]
+    fn from(signal: Signal) -> Self {
+        match signal {
+            Signal::Inexact => Error::Inexact,
+            Signal::Overflow | Signal::Underflow => Error::ExponentOverflow,
+            Signal::DivisionByZero => Error::DivisionByZero,
+            Signal::InvalidOperation => Error::Domain,
+        }
+    }
+}
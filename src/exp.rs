@@ -0,0 +1,46 @@
+use ibig::{IBig, ibig};
+use crate::{repr::FloatRepr, context::Context, utils::get_precision};
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the exponential function (`e^x`)
+    pub fn exp(&self) -> Self {
+        Context::bounded_value(|| self.exp_unbounded())
+    }
+
+    fn exp_unbounded(&self) -> Self {
+        let precision = self.precision;
+        if self.mantissa == ibig!(0) {
+            return Self::from_integer(ibig!(1), precision);
+        }
+
+        // reduce the argument by exp(x) = exp(x / 2^k)^(2^k), so that |x / 2^k| < 2^-s,
+        // where s is chosen to balance the cost of the series and the squarings
+        let log2_e = (E as f64).log2();
+        let bits = (precision as f64 * log2_e) as usize + 1;
+        let s = (bits as f64).sqrt() as usize / 2 + 1;
+        let top_bits = ((self.log_radix_floor() + 1) as f64 * log2_e).max(0.) as usize;
+        let k = top_bits + s;
+
+        // each squaring doubles the relative error, and there are at most `bits / s` terms
+        let guard = ((k as f64 / log2_e) as usize) + get_precision::<E>(&IBig::from(bits / s + 1)) + 2;
+        let wp = precision + guard;
+
+        let x = self.clone().with_precision(wp) / Self::from_integer(ibig!(1) << k, wp);
+        let mut sum = Self::from_integer(ibig!(1), wp);
+        let mut term = sum.clone();
+        let mut n = 1usize;
+        loop {
+            term = &term * &x / Self::from_integer(IBig::from(n), wp);
+            if term.mantissa == ibig!(0) || term.log_radix_floor() < sum.log_radix_floor() - wp as isize {
+                break;
+            }
+            sum = sum + term.clone();
+            n += 1;
+        }
+
+        for _ in 0..k {
+            sum = &sum * &sum;
+        }
+        sum.with_precision(precision)
+    }
+}
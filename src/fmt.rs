@@ -1,9 +1,8 @@
 //! Implementation of formatters
 
 use core::fmt::{self, Display, Formatter, Write};
-use std::convert::TryInto;
-use ibig::{ibig, ops::Abs};
-use crate::{repr::FloatRepr, utils::{shr_rem_radix, round_with_rem, shr_radix, get_precision}};
+use ibig::{IBig, ibig};
+use crate::{repr::FloatRepr, utils::shr_round_radix, ibig_ext::{magnitude, PowerTree}};
 
 // TODO: implement Debug using mantissa * radix ^ exponent (prec: xxx),
 // FIXME: sign, width and fill options are not yet correctly handled
@@ -16,76 +15,63 @@ impl<const E: usize, const R: u8> Display for FloatRepr<E, R> {
         }
 
         if self.exponent < 0 {
+            // round the mantissa first if the required precision is lower, which could carry into the integral part
             let exp = -self.exponent as usize;
-            let (trunc, frac) = shr_rem_radix::<E>(&self.mantissa, exp);
-            let frac_prec = get_precision::<E>(&frac);
-            assert!(frac_prec <= exp);
-            let mut frac = frac.abs(); // don't print sign for fractional part
+            let (mantissa, exp) = match f.precision() {
+                Some(v) if v < exp => (shr_round_radix::<E>(&self.mantissa, exp - v, R), v),
+                _ => (self.mantissa.clone(), exp)
+            };
+            // split the digits of the mantissa instead of dividing it by a power of the radix
+            let digits = digits::<E>(&mantissa);
+            let (trunc, frac) = digits.split_at(digits.len().saturating_sub(exp));
+            let frac_zeros = exp - frac.len(); // the fractional part actually has exp digits with left zero padding
+            let has_frac = frac.bytes().any(|b| b != b'0');
 
             // print integral part
-            if trunc == ibig!(0) && self.mantissa < ibig!(0) {
-                f.write_char('-')?;
-            }
-            trunc.in_radix(E as u32).fmt(f)?;
+            f.pad_integral(self.mantissa >= ibig!(0), "", if trunc.is_empty() { "0" } else { trunc })?;
 
             // print fractional part
-            // note that the fractional part has actually exp digits (with left zero padding)
             if let Some(v) = f.precision() {
-                if v != 0 {
-                    f.write_char('.')?;
-                    if exp >= v {
-                        // shrink fractional part if it exceeds the required precision
-                        // there could be one more digit in the fractional part after rounding
-                        let new_prec = if exp == v {
-                            frac_prec
-                        } else if frac_prec > exp - v {
-                            let (shifted, mut rem) = shr_rem_radix::<E>(&frac, exp - v);
-                            frac = shifted;
-                            shr_radix::<E>(&mut rem, exp - v - 1);
-                            round_with_rem::<E, R>(&mut frac, rem.try_into().unwrap());
-                            get_precision::<E>(&frac)
-                        } else {
-                            0
-                        };
-
-                        if v > new_prec {
-                            for _ in 0..v - new_prec {
-                                f.write_char('0')?;
-                            }
-                        }
-                        if frac_prec > exp - v {
-                            frac.in_radix(E as u32).fmt(f)?;
-                        }
-                    } else {
-                        // append zeros if the required precision is larger
-                        for _ in 0..exp - frac_prec {
-                            f.write_char('0')?;
-                        }
-                        frac.in_radix(E as u32).fmt(f)?;
-                        for _ in 0..v - exp {
-                            f.write_char('0')?; // TODO: padding handling is not correct here
-                        }
-                    }
-                }
                 // don't print any fractional part if precision is zero
-            } else {
-                if frac_prec > 0 {
+                if v != 0 {
                     f.write_char('.')?;
-                    for _ in 0..(exp - frac_prec) {
+                    for _ in 0..frac_zeros {
                         f.write_char('0')?;
                     }
-                    frac.in_radix(E as u32).fmt(f)?;
+                    f.write_str(frac)?;
+                    // append zeros if the required precision is larger
+                    for _ in exp..v {
+                        f.write_char('0')?; // TODO: padding handling is not correct here
+                    }
+                }
+            } else if has_frac {
+                f.write_char('.')?;
+                for _ in 0..frac_zeros {
+                    f.write_char('0')?;
                 }
+                f.write_str(frac)?;
             }
         } else {
             // directly print the mantissa and append zeros if needed
             // precision doesn't make a difference since we force printing in native radix
-            self.mantissa.in_radix(E as u32).fmt(f)?;
-            for _ in 0..self.exponent {
-                f.write_char('0')?;
-            }
+            let mut digits = digits::<E>(&self.mantissa);
+            digits.extend(core::iter::repeat_n('0', self.exponent as usize));
+            f.pad_integral(self.mantissa >= ibig!(0), "", &digits)?;
         };
 
         Ok(())
     }
 }
+
+/// Get the digits of the magnitude of the mantissa in the radix. The conversion of ibig is linear
+/// for the radices that are powers of two, and the others are converted by divide-and-conquer
+/// (see [PowerTree::write_digits]), so that printing a huge number is quasi-linear.
+pub(crate) fn digits<const E: usize>(mantissa: &IBig) -> String {
+    let magnitude = magnitude(mantissa);
+    if E.is_power_of_two() {
+        return magnitude.in_radix(E as u32).to_string();
+    }
+    let mut digits = String::new();
+    PowerTree::new(E).write_digits(&magnitude, 1, &mut digits);
+    digits
+}
@@ -1,6 +1,7 @@
 //! TODO: Extension to ibig that should be upstreamed.
 
-use ibig::{IBig, UBig, ibig, ubig, ops::UnsignedAbs};
+use core::convert::TryFrom;
+use ibig::{IBig, UBig, ibig, ubig, ops::{DivRem, UnsignedAbs}};
 
 // REF: https://en.wikipedia.org/wiki/Exponential_search
 //      https://people.csail.mit.edu/jaffer/III/ilog.pdf
@@ -18,9 +19,15 @@ pub fn log_rem(x: &UBig, base: usize) -> (usize, UBig) {
         return (bits - 1, rem);
     }
 
-    // very naive algorithm by now
-    let mut counter = 0;
-    let mut exp = ubig!(1);
+    // start from an estimate based on the bit length (base^e <= 2^bits), and correct it by single
+    // factors of the base, so that the result is exact however the estimate is rounded
+    let bits = x.bit_len();
+    let mut counter = ((bits - 1) as f64 / (base as f64).log2()) as usize;
+    let mut exp = UBig::from(base).pow(counter);
+    while &exp > x {
+        exp /= base;
+        counter -= 1;
+    }
 
     loop {
         let new_exp = &exp * base;
@@ -39,12 +46,31 @@ pub fn log_pow_rem(x: &UBig, exp: usize, base: usize) -> (usize, UBig) {
 }
 
 /// Calculate log_base(x^exp), return the floored value
+#[inline]
 pub fn log_pow(x: &UBig, exp: usize, base: usize) -> usize {
     log_pow_rem(x, exp, base).0
 }
 
-#[inline]
+/// Calculate the floored log_base(x).
 pub fn log(x: &UBig, base: usize) -> usize {
+    if base == 2 {
+        return x.bit_len() - 1;
+    }
+
+    // the short numbers are the most common, which are compared with the powers in machine integers
+    if let Ok(x) = u128::try_from(x) {
+        let base = base as u128;
+        let mut counter = 0;
+        let mut power = base;
+        while power <= x {
+            counter += 1;
+            match power.checked_mul(base) {
+                Some(next) => power = next,
+                None => break,
+            }
+        }
+        return counter;
+    }
     log_rem(x, base).0
 }
 
@@ -52,13 +78,129 @@ pub fn magnitude(x: &IBig) -> UBig {
     x.clone().unsigned_abs()
 }
 
-pub fn remove_pow(x: &mut IBig, base: &IBig) -> UBig {
-    let mut counter = ubig!(0);
-    while &*x % base == ibig!(0) {
-        *x /= base;
-        counter += 1u8;
+/// Remove the factors of `base` from x, and return the number of factors removed (see [PowerTree::remove_pow])
+#[inline]
+pub fn remove_pow(x: &mut IBig, base: usize) -> usize {
+    PowerTree::new(base).remove_pow(x)
+}
+
+/// The powers `base^(2^k)` of a base, which are computed by repeated squaring and cached,
+/// so that they are shared by the divide-and-conquer algorithms on numbers of the base.
+pub struct PowerTree {
+    base: usize,
+    powers: Vec<UBig>,
+}
+
+/// The bit length below which [PowerTree::write_digits] uses the conversion of ibig directly
+const DIGITS_SPLIT_THRESHOLD: usize = 4096;
+
+impl PowerTree {
+    pub fn new(base: usize) -> Self {
+        Self { base, powers: vec![UBig::from(base)] }
+    }
+
+    /// Get `base^(2^k)`
+    pub fn get(&mut self, k: usize) -> &UBig {
+        while self.powers.len() <= k {
+            let last = self.powers.last().unwrap();
+            let square = last * last;
+            self.powers.push(square);
+        }
+        &self.powers[k]
+    }
+
+    /// Calculate `base^n` as the product of the cached powers for the bits of n
+    pub fn pow(&mut self, n: usize) -> UBig {
+        let mut result = ubig!(1);
+        for k in 0..(usize::BITS - n.leading_zeros()) as usize {
+            if (n >> k) & 1 == 1 {
+                result *= self.get(k);
+            }
+        }
+        result
+    }
+
+    /// Remove the factors of the base from x, and return the number of factors removed.
+    ///
+    /// The number is divided by `base^(2^k)` for increasing k until it's not divisible, and then
+    /// by the smaller powers in reverse order, so the number of divisions is logarithmic in the count.
+    /// The powers longer than the number can't divide it, so they are never computed.
+    pub fn remove_pow(&mut self, x: &mut IBig) -> usize {
+        if x == &ibig!(0) {
+            return 0;
+        }
+
+        let mut rest = magnitude(x);
+        let mut counter = 0;
+        let mut k = 0;
+        loop {
+            let power = self.get(k);
+            if power.bit_len() > rest.bit_len() {
+                break;
+            }
+            let (q, r) = (&rest).div_rem(power);
+            if r != ubig!(0) {
+                break;
+            }
+            rest = q;
+            counter += 1 << k;
+            k += 1;
+        }
+        if counter == 0 {
+            return 0;
+        }
+
+        // the remaining count is less than 2^k, find its bits from the top
+        for i in (0..k).rev() {
+            let (q, r) = (&rest).div_rem(&self.powers[i]);
+            if r == ubig!(0) {
+                rest = q;
+                counter += 1 << i;
+            }
+        }
+        *x = if *x < ibig!(0) { -IBig::from(rest) } else { IBig::from(rest) };
+        counter
+    }
+
+    /// Write the digits of x in the base (which is at most 36), padded with zeros to at least `len` digits.
+    /// Nothing is written for zero unless it's padded.
+    ///
+    /// A long number is divided by the cached power `base^(2^k)` with about half of its length, and the
+    /// quotient and the remainder are written recursively, so the cost is quasi-linear instead of quadratic.
+    pub fn write_digits(&mut self, x: &UBig, len: usize, out: &mut String) {
+        let bits = x.bit_len();
+        if bits <= DIGITS_SPLIT_THRESHOLD {
+            let digits = if bits == 0 { String::new() } else { x.in_radix(self.base as u32).to_string() };
+            out.extend(core::iter::repeat_n('0', len.saturating_sub(digits.len())));
+            out.push_str(&digits);
+            return;
+        }
+
+        let mut k = 0;
+        while self.get(k + 1).bit_len() <= bits / 2 {
+            k += 1;
+        }
+        let (q, r) = x.div_rem(&self.powers[k]);
+        self.write_digits(&q, len.saturating_sub(1 << k), out);
+        self.write_digits(&r, 1 << k, out);
+    }
+}
+
+/// Calculate the floored square root of x.
+pub fn sqrt(x: &UBig) -> UBig {
+    if x == &ubig!(0) {
+        return ubig!(0);
+    }
+
+    // newton iteration starting from a value not less than the root
+    let mut guess = ubig!(1) << x.bit_len().div_ceil(2);
+    loop {
+        let next = (&guess + x / &guess) >> 1;
+        if next >= guess {
+            break guess;
+        }
+        guess = next;
     }
-    return counter;
 }
 
 #[cfg(test)]
@@ -73,4 +215,78 @@ mod tests {
         assert_eq!(log_rem(&ubig!(3), 10), (0, ubig!(2)));
         assert_eq!(log_rem(&ubig!(13), 10), (1, ubig!(3)));
     }
+
+    #[test]
+    fn test_log_pow() {
+        assert_eq!(log_pow(&ubig!(10), 0, 2), 0);
+        assert_eq!(log_pow(&ubig!(2), 53, 10), 15);
+        assert_eq!(log_pow(&ubig!(10), 100, 10), 100);
+        assert_eq!(log_pow(&ubig!(16), 3, 2), 12);
+        for (x, exp, base, log) in [(10, 1000, 2, 3321), (2, 12345, 10, 3716), (3, 777, 7, 438), (36, 500, 10, 778)] {
+            assert_eq!(log_pow(&UBig::from(x as u8), exp, base), log);
+        }
+    }
+
+    #[test]
+    fn test_log() {
+        for base in [3, 7, 10, 36] {
+            let power = UBig::from(base).pow(200);
+            assert_eq!(log(&power, base), 200);
+            assert_eq!(log(&(&power - 1u8), base), 199);
+            assert_eq!(log(&(&power + 1u8), base), 200);
+            assert_eq!(log(&(&power * 2u8), base), 200);
+        }
+    }
+
+    #[test]
+    fn test_remove_pow() {
+        let mut x = ibig!(0);
+        assert_eq!(remove_pow(&mut x, 10), 0);
+        let mut x = ibig!(-123);
+        assert_eq!(remove_pow(&mut x, 10), 0);
+        assert_eq!(x, ibig!(-123));
+        let mut x = ibig!(-1200);
+        assert_eq!(remove_pow(&mut x, 10), 2);
+        assert_eq!(x, ibig!(-12));
+        for n in [1, 7, 8, 1000, 1023, 1024] {
+            let mut x = ibig!(7) * ibig!(3).pow(n);
+            assert_eq!(remove_pow(&mut x, 3), n);
+            assert_eq!(x, ibig!(7));
+            let mut x = ibig!(3).pow(n);
+            assert_eq!(remove_pow(&mut x, 3), n);
+            assert_eq!(x, ibig!(1));
+        }
+    }
+
+    #[test]
+    fn test_power_tree() {
+        let mut tree = PowerTree::new(7);
+        assert_eq!(tree.get(3), &ubig!(7).pow(8));
+        assert_eq!(tree.pow(0), ubig!(1));
+        assert_eq!(tree.pow(1000), ubig!(7).pow(1000));
+
+        let mut digits = String::new();
+        PowerTree::new(10).write_digits(&ubig!(0), 3, &mut digits);
+        PowerTree::new(10).write_digits(&ubig!(12), 3, &mut digits);
+        assert_eq!(digits, "000012");
+        for base in [3, 10, 36] {
+            let x = UBig::from(base as u8).pow(5000) * 17u8 + 5u8;
+            let mut digits = String::new();
+            PowerTree::new(base).write_digits(&x, 0, &mut digits);
+            assert_eq!(digits, x.in_radix(base as u32).to_string());
+            let mut digits = String::new();
+            PowerTree::new(base).write_digits(&x, 6000, &mut digits);
+            assert_eq!(digits.len(), 6000);
+            assert_eq!(digits.trim_start_matches('0'), x.in_radix(base as u32).to_string());
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        assert_eq!(sqrt(&ubig!(0)), ubig!(0));
+        assert_eq!(sqrt(&ubig!(1)), ubig!(1));
+        assert_eq!(sqrt(&ubig!(15)), ubig!(3));
+        assert_eq!(sqrt(&ubig!(16)), ubig!(4));
+        assert_eq!(sqrt(&(ubig!(10).pow(40) - 1u8)), ubig!(10).pow(20) - 1u8);
+    }
 }
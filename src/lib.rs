@@ -1,15 +1,17 @@
-/// - Rounding is ensured in type level
-/// - Precision is stored inside the numbers
-/// - The higher precision will be used if two oprands have different precision
-/// - Conversion from f32 and f64 is only implemented for BinaryRepr
-/// - Conversion from and to str is limited to native radix. To print or parse with different
-///   radix, use FloatRepr::with_radix() to convert. (printing with certain radices is permitted,
-///   but need to specify explicitly, to print decimal numbers, one can use scientific representation
-///   or use the alternate flag)
+//! - Rounding is ensured in type level
+//! - Precision is stored inside the numbers
+//! - The higher precision will be used if two oprands have different precision, other choices
+//!   are available through [PrecisionPolicy] (e.g. [FloatRepr::add_with_policy])
+//! - Conversion from f32 and f64 is only implemented for BinaryRepr
+//! - Conversion from and to str is limited to native radix. To print or parse with different
+//!   radix, use FloatRepr::with_radix() to convert. (printing with certain radices is permitted,
+//!   but need to specify explicitly, to print decimal numbers, one can use scientific representation
+//!   or use the alternate flag)
 
 // TODO: reference crates: twofloat, num-bigfloat, rust_decimal, bigdecimal
 
 mod add;
+mod approx;
 mod convert;
 mod fmt;
 mod repr;
@@ -19,8 +21,28 @@ mod sign;
 mod utils;
 mod mul;
 mod div;
+mod root;
+mod exp;
+mod log;
+mod erf;
+mod consts;
+mod special;
+mod agm;
+mod cmp;
+mod context;
+mod error;
+mod scaled;
+mod ulp;
+mod policy;
+#[cfg(feature = "rand_core")]
+mod rand;
 
-pub use repr::{FloatRepr, BinaryRepr, DecimalRepr, RoundingMode};
+pub use repr::{FloatRepr, BinaryRepr, DecimalRepr, RoundingMode, Rounding};
+pub use approx::{Approximation, RoundingDirection};
+pub use context::{Context, Signal, Signals};
+pub use error::Error;
+pub use scaled::ScaledRepr;
+pub use policy::PrecisionPolicy;
 
 /// Multi-precision float number with binary exponent and [RoundingMode::HalfEven] rounding mode
 #[allow(non_upper_case_globals)]
@@ -28,6 +50,9 @@ pub type FBig = BinaryRepr<{RoundingMode::HalfEven}>;
 /// Multi-precision decimal number with decimal exponent and [RoundingMode::HalfEven] rounding mode
 #[allow(non_upper_case_globals)]
 pub type DBig = DecimalRepr<{RoundingMode::HalfEven}>;
+/// Multi-precision decimal number that preserves its scale, with [RoundingMode::HalfEven] rounding mode
+#[allow(non_upper_case_globals)]
+pub type ScaledDBig = ScaledRepr<10, {RoundingMode::HalfEven}>;
 
 // TODO: make no_std
 // TODO: add macro fbig!, dbig!, support parsing scientific repr, and set rounding mode
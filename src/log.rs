@@ -0,0 +1,111 @@
+use ibig::{IBig, ibig};
+use crate::{repr::FloatRepr, context::Context, utils::{get_precision, newton_schedule}};
+
+/// The precision (in bits) above which the logarithm is evaluated with the AGM,
+/// which is about 10k decimal digits
+const LN_AGM_THRESHOLD: f64 = 33_000.;
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the natural logarithm of the number
+    /// 
+    /// For very high precision (more than about 10k decimal digits), the logarithm is evaluated
+    /// by the AGM based formula `ln(x) ~ pi / (2 agm(1, 4/s)) - m ln(2)` where `s = x 2^m`,
+    /// because the series and Newton iterations on [Self::exp] become too slow.
+    /// 
+    /// # Panics
+    /// If the number is not positive
+    pub fn ln(&self) -> Self {
+        Context::bounded_value(|| self.ln_unbounded())
+    }
+
+    fn ln_unbounded(&self) -> Self {
+        assert!(self.mantissa > ibig!(0), "logarithm of non-positive number");
+        let precision = self.precision;
+        if precision as f64 * (E as f64).log2() > LN_AGM_THRESHOLD {
+            return self.ln_agm(precision);
+        }
+
+        let estimate = self.ln_f64();
+        if estimate.abs() < 0.5 {
+            // use the series ln(x) = 2 atanh((x-1)/(x+1)) when x is close to 1
+            return self.ln_atanh(precision);
+        }
+
+        // otherwise use halley iterations y = y + 2(x - e^y) / (x + e^y)
+        let full = precision + 2;
+        let init = (50. / (E as f64).log2()) as usize;
+        let mut y = FloatRepr::<2, R>::from(estimate).with_radix::<E>();
+        for wp in newton_schedule(init, full) {
+            let x = self.clone().with_precision(wp);
+            let y_wp = y.with_precision(wp);
+            let exp = y_wp.exp();
+            let step = (&x - &exp) / (x + exp);
+            y = &y_wp + &(&step + &step);
+        }
+        y.with_precision(precision)
+    }
+
+    /// Evaluate `ln(x) = 2 (z + z^3/3 + z^5/5 + ...)` where `z = (x-1)/(x+1)`
+    fn ln_atanh(&self, precision: usize) -> Self {
+        // x - 1 is calculated exactly, so that the relative error is small even if x is very close to 1
+        let num = self.sub_one_exact();
+        if num.mantissa == ibig!(0) {
+            return num.with_precision(precision);
+        }
+
+        // |z| < 1/4, so each term adds at least one more bit
+        let terms = (precision as f64 * (E as f64).log2() / 2.) as usize + 1;
+        let wp = precision + get_precision::<E>(&IBig::from(terms)) + 2;
+        let one = Self::from_integer(ibig!(1), wp);
+        let z = num.with_precision(wp) / (self.clone().with_precision(wp) + one);
+        let z2 = &z * &z;
+
+        let mut sum = z.clone();
+        let mut power = z;
+        let mut n = 1usize;
+        loop {
+            power = &power * &z2;
+            let term = &power / &Self::from_integer(IBig::from(2 * n + 1), wp);
+            if term.mantissa == ibig!(0) || term.log_radix_floor() < sum.log_radix_floor() - wp as isize {
+                break;
+            }
+            sum = sum + term;
+            n += 1;
+        }
+        (&sum + &sum).with_precision(precision)
+    }
+
+    /// Evaluate `ln(x) = pi / (2 agm(1, 4/s)) - m ln(2)` where `s = x 2^m`, the relative
+    /// error of the formula is about `1 / s^2`, so it's chosen that `s > 2^(bits / 2)`.
+    fn ln_agm(&self, precision: usize) -> Self {
+        let estimate = self.ln_f64();
+
+        // the result cancels if x is close to 1, estimate the loss by x - 1
+        let cancel = if estimate.abs() < 0.5 {
+            let num = self.sub_one_exact();
+            if num.mantissa == ibig!(0) {
+                return num.with_precision(precision);
+            }
+            (-num.log_radix_floor()).max(0) as usize + 1
+        } else {
+            0
+        };
+
+        // m ln(2) is about bits / 2 ln(2), which has about log_radix(precision) more integral digits
+        let bits = precision as f64 * (E as f64).log2();
+        let wp = precision + cancel + get_precision::<E>(&IBig::from(bits as usize)) + 3;
+        let bits = wp as f64 * (E as f64).log2();
+        let m = (bits / 2. + 2. - estimate / core::f64::consts::LN_2).max(0.) as usize;
+
+        let mut pow2 = ibig!(1);
+        pow2 <<= m;
+        let s = self.clone().with_precision(wp) * Self::from_parts(pow2, 0).with_precision(wp);
+        let one = Self::from_integer(ibig!(1), wp);
+        let four = Self::from_integer(ibig!(4), wp);
+        let agm = one.agm(&(four / s));
+
+        let two = Self::from_integer(ibig!(2), wp);
+        let m_ln2 = Self::from_integer(IBig::from(m), wp) * Self::ln2(wp);
+        (Self::pi(wp) / (two * agm) - m_ln2).with_precision(precision)
+    }
+}
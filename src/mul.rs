@@ -1,15 +1,139 @@
 use core::ops::Mul;
-use crate::{repr::FloatRepr, utils::mul_hi};
+use ibig::{IBig, ibig};
+use crate::{
+    repr::{FloatRepr, Rounding},
+    approx::{Approximation, RoundingDirection},
+    context::{Context, Signal},
+    error::Error,
+    utils::{get_precision, shl_radix, shr_rem_radix}
+};
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the integer power of the number by repeated squaring
+    pub(crate) fn powi(&self, exp: usize) -> Self {
+        let mut result = Self::from_integer(ibig!(1), self.precision);
+        let mut base = self.clone();
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Multiply two numbers, and round the product with a rounding mode selected at runtime
+    #[inline]
+    pub fn mul_rounded(&self, rhs: &Self, rounding: Rounding) -> Self {
+        self.mul_with_mode(rhs, rounding as u8).value()
+    }
+
+    /// Multiply two numbers, and report whether the product is exact and
+    /// the direction of the rounding if it's not.
+    #[inline]
+    pub fn mul_approx(&self, rhs: &Self) -> Approximation<Self, RoundingDirection> {
+        self.mul_with_mode(rhs, R)
+    }
+
+    /// Multiply two numbers, returning an error instead of panicking if the exponent overflows
+    pub fn checked_mul(&self, rhs: &Self) -> Result<Self, Error> {
+        if self.mantissa != ibig!(0) && rhs.mantissa != ibig!(0) {
+            let digits = self.actual_precision() + rhs.actual_precision();
+            self.exponent.checked_add(rhs.exponent)
+                .and_then(|exponent| exponent.checked_add_unsigned(digits))
+                .ok_or(Error::ExponentOverflow)?;
+        }
+        Ok(self.try_mul_with_mode(rhs, R)?.value())
+    }
+
+    /// Multiply two numbers without rounding, the precision of the result is the larger one
+    /// of the two operands, and it's grown if the exact product needs more digits.
+    pub fn mul_exact(&self, rhs: &Self) -> Self {
+        let precision = self.precision.max(rhs.precision);
+        let mantissa = &self.mantissa * &rhs.mantissa;
+        let exponent = self.exponent.checked_add(rhs.exponent).expect("exponent overflow");
+        Self::from_parts(mantissa, exponent).with_min_precision(precision)
+    }
+
+    #[inline]
+    pub(crate) fn mul_with_mode(&self, rhs: &Self, mode: u8) -> Approximation<Self, RoundingDirection> {
+        Context::expect_in_range(self.try_mul_with_mode(rhs, mode))
+    }
+
+    /// Multiply two numbers limited to the thread context (see [Context::bounded])
+    pub(crate) fn try_mul_with_mode(&self, rhs: &Self, mode: u8) -> Result<Approximation<Self, RoundingDirection>, Signal> {
+        let precision = self.precision.max(rhs.precision);
+        Context::bounded(precision, mode, |precision, mode| self.mul_to_precision(rhs, precision, mode))
+    }
+
+    fn mul_to_precision(&self, rhs: &Self, precision: usize, mode: u8) -> Approximation<Self, RoundingDirection> {
+        // the product is calculated exactly and then rounded to the desired precision
+        let mantissa = &self.mantissa * &rhs.mantissa;
+        let exponent = self.exponent.checked_add(rhs.exponent).expect("exponent overflow");
+        Self::from_parts(mantissa, exponent).with_precision_mode_approx(precision, mode)
+    }
+
+    /// Calculate the sum of squares of the numbers, the sum is calculated exactly
+    /// and then rounded once to the largest precision of the inputs.
+    pub fn sum_of_squares<'a>(values: impl IntoIterator<Item = &'a Self>) -> Self {
+        let values: Vec<&Self> = values.into_iter().collect();
+        let precision = values.iter().map(|v| v.precision).max().unwrap_or(0);
+        let sum = Context::bounded(precision, R, |precision, mode| {
+            let (mantissa, exponent) = Self::sum_of_squares_truncated(&values, precision + 2);
+            Self::from_parts(mantissa, exponent).with_precision_mode_approx(precision, mode)
+        });
+        Context::expect_in_range(sum).value()
+    }
+
+    /// Calculate the sum of squares as raw parts, keeping at least `digits` digits exactly.
+    /// Lower digits are truncated and replaced by a sticky digit, so that the result can
+    /// be correctly rounded to less than `digits` digits.
+    pub(crate) fn sum_of_squares_truncated(values: &[&Self], digits: usize) -> (IBig, isize) {
+        let squares: Vec<(IBig, isize)> = values.iter()
+            .filter(|v| v.mantissa != ibig!(0))
+            .map(|v| (&v.mantissa * &v.mantissa, v.exponent.checked_mul(2).expect("exponent overflow")))
+            .collect();
+        let max_top = match squares.iter().map(|(m, e)| e + get_precision::<E>(m) as isize).max() {
+            Some(top) => top,
+            None => return (ibig!(0), 0)
+        };
+
+        // all squares are positive, so the digits below the floor don't affect the top digits
+        let min_exp = squares.iter().map(|(_, e)| *e).min().unwrap();
+        let base = min_exp.max(max_top - digits as isize);
+        let mut sum = ibig!(0);
+        let mut sticky = false;
+        for (mut m, e) in squares {
+            if e >= base {
+                shl_radix::<E>(&mut m, (e - base) as usize);
+                sum += m;
+            } else {
+                let (q, r) = shr_rem_radix::<E>(&m, (base - e) as usize);
+                sticky |= r != ibig!(0);
+                sum += q;
+            }
+        }
+
+        if sticky {
+            shl_radix::<E>(&mut sum, 1);
+            sum += 1u8;
+            (sum, base - 1)
+        } else {
+            (sum, base)
+        }
+    }
+}
 
 impl<const E: usize, const R: u8> Mul for &FloatRepr<E, R> {
     type Output = FloatRepr<E, R>;
 
     #[inline]
     fn mul(self, rhs: Self) -> Self::Output {
-        let precision = self.precision.max(rhs.precision);
-        let mantissa = mul_hi::<E>(&self.mantissa, &rhs.mantissa, precision + 1);
-        let exponent = self.exponent + rhs.exponent;
-        FloatRepr { mantissa, exponent, precision: precision + 1 }.with_precision(precision)
+        self.mul_with_mode(rhs, R).value()
     }
 }
 
@@ -1,7 +1,7 @@
 use core::str::FromStr;
 use core::num::IntErrorKind;
 use ibig::{IBig, error::ParseError};
-use crate::{repr::FloatRepr, utils::get_precision};
+use crate::{repr::FloatRepr, error::Error};
 
 impl<const E: usize, const R: u8> FromStr for FloatRepr<E, R> {
     type Err = ParseError;
@@ -20,37 +20,59 @@ impl<const E: usize, const R: u8> FromStr for FloatRepr<E, R> {
     ///
     /// # Panics
     ///
-    /// Panics if `Radix` is not supported by [ibig]. (currently only 2 ~ 36 is supported)
+    /// Panics if `Radix` is not supported by [ibig] (currently only 2 ~ 36 is supported),
+    /// or the exponent overflows. Use [FloatRepr::checked_from_str] to get these as errors.
     ///
-    fn from_str(mut src: &str) -> Result<Self, ParseError> {
-        assert!(E >= 2 && E <= 36, "radix is not supported");
+    fn from_str(src: &str) -> Result<Self, ParseError> {
+        match Self::checked_from_str(src) {
+            Ok(value) => Ok(value),
+            Err(Error::Parse(e)) => Err(e),
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Convert a string in a given base to [FloatRepr] (see [FromStr::from_str] for the format),
+    /// returning an error instead of panicking if the radix is not supported or the exponent overflows
+    pub fn checked_from_str(src: &str) -> Result<Self, Error> {
+        let (mantissa, exponent) = Self::parse_parts(src)?;
+        Ok(Self::from_parts(mantissa, exponent))
+    }
+
+    /// Parse the string into the mantissa and the exponent as they are written,
+    /// without removing the trailing zeros
+    pub(crate) fn parse_parts(mut src: &str) -> Result<(IBig, isize), Error> {
+        if !(2..=36).contains(&E) {
+            return Err(Error::InvalidRadix);
+        }
 
         // determine the position of scale markers
         let e_pos = match (src.rfind('E'), src.rfind('e')) {
-            (Some(_), Some(_)) => return Err(ParseError::InvalidDigit),
+            (Some(_), Some(_)) => return Err(ParseError::InvalidDigit.into()),
             (Some(a), None) => Some(a),
             (None, Some(b)) => Some(b),
             (None, None) => None
         };
 
         let p_pos = match (src.rfind('P'), src.rfind('p')) {
-            (Some(_), Some(_)) => return Err(ParseError::InvalidDigit),
+            (Some(_), Some(_)) => return Err(ParseError::InvalidDigit.into()),
             (Some(a), None) => Some(a),
             (None, Some(b)) => Some(b),
             (None, None) => None
         };
 
         let scale_pos = match (e_pos, p_pos) {
-            (Some(_), Some(_)) => return Err(ParseError::InvalidDigit),
+            (Some(_), Some(_)) => return Err(ParseError::InvalidDigit.into()),
             (Some(e), None) => {
                 if E != 10 {
-                    return Err(ParseError::InvalidDigit);
+                    return Err(ParseError::InvalidDigit.into());
                 }
                 Some(e)
             },
             (None, Some(p)) => {
                 if E != 16 {
-                    return Err(ParseError::InvalidDigit);
+                    return Err(ParseError::InvalidDigit.into());
                 }
                 Some(p)
             },
@@ -61,8 +83,8 @@ impl<const E: usize, const R: u8> FromStr for FloatRepr<E, R> {
         let scale = if let Some(pos) = scale_pos {
             let value = match isize::from_str_radix(&src[pos+1..], E as u32) {
                 Err(e) => match e.kind() {
-                    IntErrorKind::Empty => return Err(ParseError::NoDigits),
-                    _ => return Err(ParseError::InvalidDigit),
+                    IntErrorKind::Empty => return Err(ParseError::NoDigits.into()),
+                    _ => return Err(ParseError::InvalidDigit.into()),
                 },
                 Ok(v) => v
             };
@@ -75,29 +97,31 @@ impl<const E: usize, const R: u8> FromStr for FloatRepr<E, R> {
         // parse the body of the float number
         let result = match (src.find('.'), scale) {
             (None, None) => {
-                let mantissa = IBig::from_str_radix(&src, E as u32)?;
-                Self::from_parts(mantissa, 0)
+                let mantissa = IBig::from_str_radix(src, E as u32)?;
+                (mantissa, 0)
             }
             (Some(dot), None) => {
-                let trunc = IBig::from_str_radix(&src[..dot], E as u32)?;
-                let fract = IBig::from_str_radix(&src[dot+1..], E as u32)?;
-                
-                let fract_digits = get_precision::<E>(&fract);
-                let mantissa = trunc * IBig::from(E).pow(fract_digits) + fract;
-                Self::from_parts(mantissa, -(fract_digits as isize))
+                // parse the digits as a whole, so that the sign and leading zeros of the fractional part are kept
+                let digits = [&src[..dot], &src[dot+1..]].concat();
+                let mantissa = IBig::from_str_radix(&digits, E as u32)?;
+                let fract_digits = src.len() - dot - 1;
+                (mantissa, -(fract_digits as isize))
             },
             (None, Some(s)) => {
-                let mantissa = IBig::from_str_radix(&src, E as u32)?;
-                Self::from_parts(mantissa, s)
+                let mantissa = IBig::from_str_radix(src, E as u32)?;
+                s.checked_add_unsigned(src.len()).ok_or(Error::ExponentOverflow)?;
+                (mantissa, s)
             },
             (Some(dot), Some(s)) => {
-                let trunc = IBig::from_str_radix(&src[..dot], E as u32)?;
-                let fract = IBig::from_str_radix(&src[dot+1..], E as u32)?;
-
-                let fract_digits = get_precision::<E>(&fract);
-                let mantissa = trunc * IBig::from(E).pow(fract_digits) + fract;
-                let exponent = s - fract_digits as isize;
-                Self::from_parts(mantissa, exponent)
+                // parse the digits as a whole, so that the sign and leading zeros of the fractional part are kept
+                let digits = [&src[..dot], &src[dot+1..]].concat();
+                let mantissa = IBig::from_str_radix(&digits, E as u32)?;
+                let fract_digits = src.len() - dot - 1;
+                // the normalization could move the exponent up by the number of digits
+                let exponent = s.checked_sub_unsigned(fract_digits)
+                    .filter(|e| e.checked_add_unsigned(digits.len()).is_some())
+                    .ok_or(Error::ExponentOverflow)?;
+                (mantissa, exponent)
             },
         };
 
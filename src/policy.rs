@@ -0,0 +1,96 @@
+//! Selection of the result precision for operations on numbers with different precisions
+
+use crate::repr::FloatRepr;
+
+/// Decides the precision of the result when the operands have different precisions.
+///
+/// The default operators use [PrecisionPolicy::Max].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PrecisionPolicy {
+    /// Use the larger precision of the two operands
+    Max,
+    /// Use the smaller precision of the two operands
+    Min,
+    /// Use the precision of the left operand
+    Left,
+    /// Use the given precision
+    Fixed(usize),
+}
+
+impl PrecisionPolicy {
+    /// Get the precision of the result given the precisions of the operands
+    #[inline]
+    pub fn resolve(self, lhs: usize, rhs: usize) -> usize {
+        match self {
+            PrecisionPolicy::Max => lhs.max(rhs),
+            PrecisionPolicy::Min => lhs.min(rhs),
+            PrecisionPolicy::Left => lhs,
+            PrecisionPolicy::Fixed(precision) => precision,
+        }
+    }
+}
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Add two numbers, the precision of the sum is selected by the policy
+    #[inline]
+    pub fn add_with_policy(&self, rhs: &Self, policy: PrecisionPolicy) -> Self {
+        self.add_with_precision(rhs, policy.resolve(self.precision, rhs.precision))
+    }
+
+    /// Subtract two numbers, the precision of the difference is selected by the policy
+    #[inline]
+    pub fn sub_with_policy(&self, rhs: &Self, policy: PrecisionPolicy) -> Self {
+        self.sub_with_precision(rhs, policy.resolve(self.precision, rhs.precision))
+    }
+
+    /// Multiply two numbers, the precision of the product is selected by the policy
+    #[inline]
+    pub fn mul_with_policy(&self, rhs: &Self, policy: PrecisionPolicy) -> Self {
+        self.mul_with_precision(rhs, policy.resolve(self.precision, rhs.precision))
+    }
+
+    /// Divide two numbers, the precision of the quotient is selected by the policy
+    ///
+    /// # Panics
+    /// If the divisor is zero
+    #[inline]
+    pub fn div_with_policy(&self, rhs: &Self, policy: PrecisionPolicy) -> Self {
+        self.div_with_precision(rhs, policy.resolve(self.precision, rhs.precision))
+    }
+
+    /// Add two numbers, and round the sum to the given precision.
+    ///
+    /// The operands are used exactly regardless of their precisions,
+    /// so the result is correctly rounded.
+    #[inline]
+    pub fn add_with_precision(&self, rhs: &Self, precision: usize) -> Self {
+        self.operand(precision).add_with_mode(rhs.operand(precision), R).value()
+    }
+
+    /// Subtract two numbers, and round the difference to the given precision (see [Self::add_with_precision])
+    #[inline]
+    pub fn sub_with_precision(&self, rhs: &Self, precision: usize) -> Self {
+        self.operand(precision).add_with_mode(-rhs.operand(precision), R).value()
+    }
+
+    /// Multiply two numbers, and round the product to the given precision (see [Self::add_with_precision])
+    #[inline]
+    pub fn mul_with_precision(&self, rhs: &Self, precision: usize) -> Self {
+        self.operand(precision).mul_with_mode(&rhs.operand(precision), R).value()
+    }
+
+    /// Divide two numbers, and round the quotient to the given precision (see [Self::add_with_precision])
+    ///
+    /// # Panics
+    /// If the divisor is zero
+    #[inline]
+    pub fn div_with_precision(&self, rhs: &Self, precision: usize) -> Self {
+        self.operand(precision).div_with_mode(rhs.operand(precision), R).value()
+    }
+
+    /// Take the number exactly as an operand, with the precision of the result
+    #[inline]
+    fn operand(&self, precision: usize) -> Self {
+        Self { precision, ..self.clone() }
+    }
+}
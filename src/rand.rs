@@ -0,0 +1,44 @@
+//! Stochastic rounding with random number generators from [rand_core]
+
+use ibig::{UBig, ibig, ops::UnsignedAbs};
+use rand_core::RngCore;
+use crate::{repr::FloatRepr, utils::{shl_radix, shr_rem_radix}};
+
+/// Generate a uniformly distributed integer in `[0, bound)` by rejection sampling
+fn uniform_below(bound: &UBig, rng: &mut impl RngCore) -> UBig {
+    let bits = bound.bit_len();
+    let mut bytes = vec![0u8; bits.div_ceil(8)];
+    loop {
+        rng.fill_bytes(&mut bytes);
+        let value = UBig::from_le_bytes(&bytes) >> (8 * bytes.len() - bits);
+        if &value < bound {
+            break value;
+        }
+    }
+}
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Change the precision of the number with stochastic rounding.
+    ///
+    /// The discarded part is rounded away from zero with a probability proportional to its
+    /// magnitude (relative to a unit in the last place), otherwise it's truncated, so the result
+    /// is unbiased in expectation. The result is deterministic given the state of the generator.
+    pub fn with_precision_stochastic(self, precision: usize, rng: &mut impl RngCore) -> Self {
+        let actual = self.actual_precision();
+        if actual <= precision {
+            return Self { precision, ..self };
+        }
+
+        let shift = actual - precision;
+        let (mut mantissa, rem) = shr_rem_radix::<E>(&self.mantissa, shift);
+        if rem != ibig!(0) {
+            let mut unit = ibig!(1);
+            shl_radix::<E>(&mut unit, shift);
+            let sample = uniform_below(&unit.unsigned_abs(), rng);
+            if sample < rem.clone().unsigned_abs() {
+                mantissa += rem.signum();
+            }
+        }
+        Self::from_parts(mantissa, self.exponent + shift as isize).with_precision(precision)
+    }
+}
@@ -1,6 +1,6 @@
 
-use ibig::IBig;
-use crate::utils::get_precision;
+use ibig::{IBig, ibig};
+use crate::{error::Error, utils::{get_precision, shl_radix, shr_radix, shr_rem_radix}};
 
 // FIXME: this should be a enum when enum const is supported in generic argument
 /// Defines rounding modes of the floating numbers.
@@ -21,6 +21,51 @@ pub mod RoundingMode {
 
     /// Round to the nearest value, ties away from zero
     pub const HalfAway: u8 = 4;
+
+    /// Round to the nearest value, ties toward zero
+    pub const HalfZero: u8 = 5;
+
+    /// Round to the nearest value, ties toward +infinity
+    pub const HalfUp: u8 = 6;
+
+    /// Round away from zero
+    pub const AwayFromZero: u8 = 7;
+
+    /// Round to the nearest value, ties are rounded to an odd value
+    pub const HalfOdd: u8 = 8;
+
+    /// Round to the nearest value toward zero, and to an odd value if it's inexact.
+    /// 
+    /// The result keeps the information of inexactness in the last digit, so that rounding it
+    /// again to fewer digits (at least two less) gives the correct result without double rounding.
+    pub const ToOdd: u8 = 9;
+}
+
+/// Rounding modes that can be selected at runtime, each variant corresponds to
+/// the constant with the same name in [RoundingMode].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Rounding {
+    /// Round to the nearest value, ties are rounded to an even value.
+    HalfEven = RoundingMode::HalfEven,
+    /// Round toward +infinity
+    Up = RoundingMode::Up,
+    /// Round toward -infinity
+    Down = RoundingMode::Down,
+    /// Round toward 0
+    Zero = RoundingMode::Zero,
+    /// Round to the nearest value, ties away from zero
+    HalfAway = RoundingMode::HalfAway,
+    /// Round to the nearest value, ties toward zero
+    HalfZero = RoundingMode::HalfZero,
+    /// Round to the nearest value, ties toward +infinity
+    HalfUp = RoundingMode::HalfUp,
+    /// Round away from zero
+    AwayFromZero = RoundingMode::AwayFromZero,
+    /// Round to the nearest value, ties are rounded to an odd value
+    HalfOdd = RoundingMode::HalfOdd,
+    /// Round toward zero, and to an odd value if it's inexact
+    ToOdd = RoundingMode::ToOdd,
 }
 
 /// An arbitrary precision floating number represented as `mantissa * radix^scale`
@@ -28,8 +73,12 @@ pub mod RoundingMode {
 ///
 /// The const generic parameters will be abbreviated as Radix -> E, Rounding -> R.
 /// Radix should be in range \[2, isize::MAX\], and Rounding value has to be one of [RoundingMode]
+///
+/// The exponent is only bounded by the range of [isize], and the operations panic if it overflows.
+/// Install a [Context](crate::Context) with [Context::set_current](crate::Context::set_current) to limit
+/// the exponent range of the results (see [the context docs](crate::Context#thread-context)).
 #[allow(non_upper_case_globals)]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct FloatRepr<const Radix: usize, const Rounding: u8> {
     pub(crate) mantissa: IBig,
     pub(crate) exponent: isize,
@@ -51,26 +100,269 @@ impl<const E: usize, const R: u8> FloatRepr<E, R> {
         get_precision::<E>(&self.mantissa)
     }
 
-    fn ceil(&self) -> Self {
-        unimplemented!()
+    /// Get the number of trailing zeros of the number in the radix, i.e. the largest k such that
+    /// the number is a multiple of `radix^k`. It's zero if the number is not an integer,
+    /// and [None] if the number is zero.
+    #[inline]
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        if self.mantissa == ibig!(0) {
+            None
+        } else {
+            Some(self.exponent.max(0) as usize)
+        }
+    }
+
+    /// Get the number of digits in the integral part of the number (in the radix),
+    /// which is zero if the absolute value is less than one.
+    #[inline]
+    pub fn int_digits(&self) -> usize {
+        if self.mantissa == ibig!(0) {
+            0
+        } else {
+            (self.log_radix_floor() + 1).max(0) as usize
+        }
+    }
+
+    /// Get the number of digits after the radix point that are needed to represent the number exactly
+    #[inline]
+    pub fn frac_digits(&self) -> usize {
+        self.exponent.min(0).unsigned_abs()
+    }
+
+    /// Round the number to `digits` significant digits in the radix,
+    /// the precision of the number is unchanged.
+    ///
+    /// # Panics
+    /// If `digits` is zero
+    pub fn round_sig(&self, digits: usize) -> Self {
+        assert!(digits > 0, "the number of significant digits must be positive");
+        let value = self.clone().with_precision_mode(digits, R);
+        Self { precision: self.precision, ..value }
+    }
+
+    /// Set the precision to at least the given value, and grow it further
+    /// if the mantissa has more digits, so that the number is kept exact.
+    #[inline]
+    pub(crate) fn with_min_precision(self, precision: usize) -> Self {
+        let precision = precision.max(self.actual_precision());
+        Self { precision, ..self }
+    }
+
+    /// Get the exponent of the most significant digit, i.e. the integer k
+    /// such that `radix^k <= |self| < radix^(k+1)`.
+    /// 
+    /// The result is meaningless if the number is zero, see [Self::ilogb] for a checked version.
+    #[inline]
+    pub fn log_radix_floor(&self) -> isize {
+        self.exponent + self.actual_precision() as isize - 1
+    }
+
+    /// Get the exponent of the most significant digit (see [Self::log_radix_floor]),
+    /// or [None] if the number is zero
+    #[inline]
+    pub fn ilogb(&self) -> Option<isize> {
+        if self.mantissa == ibig!(0) {
+            None
+        } else {
+            Some(self.log_radix_floor())
+        }
+    }
+
+    /// Multiply the number by `radix^n`, which is exact and only changes the exponent
+    ///
+    /// # Panics
+    /// If the exponent overflows
+    #[inline]
+    pub fn scale_radix(&self, n: isize) -> Self {
+        if self.mantissa == ibig!(0) {
+            return self.clone();
+        }
+        let exponent = self.exponent.checked_add(n).expect("exponent overflow");
+        Self { exponent, ..self.clone() }
+    }
+
+    /// Decompose the number into a fraction in `[1/radix, 1)` (with the sign of the number)
+    /// and an exponent, such that `self = fraction * radix^exponent`. Zero is decomposed into `(0, 0)`.
+    pub fn frexp(&self) -> (Self, isize) {
+        if self.mantissa == ibig!(0) {
+            return (self.clone(), 0);
+        }
+        let exponent = self.log_radix_floor() + 1;
+        (self.scale_radix(-exponent), exponent)
+    }
+
+    /// Compose a number from a fraction and an exponent as `fraction * radix^exponent`,
+    /// which is the inverse of [Self::frexp]
+    ///
+    /// # Panics
+    /// If the exponent overflows
+    #[inline]
+    pub fn ldexp(fraction: Self, exponent: isize) -> Self {
+        fraction.scale_radix(exponent)
+    }
+
+    /// Check that the exponents from `below` digits under the lowest digit to `above` digits over
+    /// the highest digit are representable, which covers the intermediate results of an operation.
+    pub(crate) fn check_exponent_range(&self, below: usize, above: usize) -> Result<(), Error> {
+        if self.mantissa == ibig!(0) {
+            return Ok(());
+        }
+        let low = self.exponent.checked_sub_unsigned(below);
+        let high = self.exponent.checked_add_unsigned(self.actual_precision() + above);
+        match (low, high) {
+            (Some(_), Some(_)) => Ok(()),
+            _ => Err(Error::ExponentOverflow)
+        }
+    }
+
+    /// Estimate the natural logarithm of the absolute value of the number.
+    /// 
+    /// The estimation is accurate to about the precision of [f64], and
+    /// it doesn't overflow for large exponents. The result is -inf if the number is zero.
+    pub(crate) fn ln_f64(&self) -> f64 {
+        if self.mantissa == ibig!(0) {
+            return f64::NEG_INFINITY;
+        }
+
+        // only keep the leading digits that fit in f64
+        let keep = (60. / (E as f64).log2()) as usize + 1;
+        let digits = self.actual_precision();
+        let shift = digits.saturating_sub(keep);
+        let mut lead = self.mantissa.clone();
+        shr_radix::<E>(&mut lead, shift);
+        lead.to_f64().abs().ln() + (self.exponent + shift as isize) as f64 * (E as f64).ln()
+    }
+
+    /// Estimate the value of the number in [f64], it's accurate to about the
+    /// precision of [f64] and saturates to zero or infinity if the exponent is too large.
+    pub(crate) fn to_f64_estimate(&self) -> f64 {
+        if self.mantissa < ibig!(0) {
+            -self.ln_f64().exp()
+        } else {
+            self.ln_f64().exp()
+        }
+    }
+
+    /// Get `1 + radix^-(precision + 3)` (or `1 - radix^-(precision + 3)` if `below` is true)
+    /// without rounding, which is a number slightly larger (or smaller) than one that doesn't
+    /// create a tie when rounded to the precision.
+    pub(crate) fn one_with_tiny(precision: usize, below: bool) -> Self {
+        let digits = precision + 3;
+        let mut mantissa = ibig!(1);
+        shl_radix::<E>(&mut mantissa, digits);
+        if below {
+            mantissa -= 1u8;
+        } else {
+            mantissa += 1u8;
+        }
+        Self::from_parts(mantissa, -(digits as isize))
+    }
+
+    /// Calculate `self - 1` without rounding
+    pub(crate) fn sub_one_exact(&self) -> Self {
+        let top = self.log_radix_floor().max(0) as usize;
+        let bottom = self.exponent.min(0).unsigned_abs();
+        let exact = top + bottom + 2;
+        self.clone().with_precision(exact) - Self::from_integer(ibig!(1), exact)
+    }
+
+    /// Round the number to an integer toward +infinity
+    pub fn ceil(&self) -> Self {
+        let (mut int, rem) = self.split_integer();
+        if rem > ibig!(0) {
+            int += 1u8;
+        }
+        Self::from_parts_with_precision(int, 0, self.precision)
+    }
+
+    /// Round the number to an integer toward -infinity
+    pub fn floor(&self) -> Self {
+        let (mut int, rem) = self.split_integer();
+        if rem < ibig!(0) {
+            int -= 1u8;
+        }
+        Self::from_parts_with_precision(int, 0, self.precision)
     }
 
-    fn floor(&self) -> Self {
-        unimplemented!()
+    /// Round the number to an integer toward zero
+    pub fn trunc(&self) -> Self {
+        let (int, _) = self.split_integer();
+        Self::from_parts_with_precision(int, 0, self.precision)
     }
 
-    fn trunc(&self) -> Self {
-        unimplemented!()
+    /// Get the fractional part of the number, which has the same sign as the number
+    pub fn fract(&self) -> Self {
+        if self.exponent >= 0 {
+            return Self { mantissa: ibig!(0), exponent: 0, precision: self.precision };
+        }
+        let (_, rem) = self.split_integer();
+        Self::from_parts_with_precision(rem, self.exponent, self.precision)
+    }
+
+    /// Round the number to a multiple of `radix^-scale`, i.e. keep `scale` digits after the radix
+    /// point (a negative scale rounds to the left of the point). The precision is unchanged.
+    #[inline]
+    pub fn quantize(&self, scale: isize) -> Self {
+        self.clone().with_exponent_mode_approx(-scale, R).value()
+    }
+
+    /// Round the number to a multiple of `radix^-scale` (see [Self::quantize]) with a
+    /// rounding mode selected at runtime
+    #[inline]
+    pub fn quantize_rounded(&self, scale: isize, rounding: Rounding) -> Self {
+        self.clone().with_exponent_mode_approx(-scale, rounding as u8).value()
+    }
+
+    /// Round the number to `digits` digits after the radix point,
+    /// it's equivalent to [Self::quantize] with a non-negative scale.
+    #[inline]
+    pub fn round_dp(&self, digits: usize) -> Self {
+        self.quantize(digits as isize)
+    }
+
+    /// Round the number to `digits` digits after the radix point and print it in the radix,
+    /// always with exactly `digits` fractional digits (trailing zeros are kept).
+    ///
+    /// # Panics
+    /// If the radix is larger than 36
+    pub fn to_fixed_scale(&self, digits: usize) -> String {
+        let rounded = self.round_dp(digits);
+        let mut mantissa = rounded.mantissa.clone();
+        if mantissa != ibig!(0) {
+            shl_radix::<E>(&mut mantissa, (rounded.exponent + digits as isize) as usize);
+        }
+
+        let mut result = String::new();
+        if mantissa < ibig!(0) {
+            result.push('-');
+        }
+        let body = crate::fmt::digits::<E>(&mantissa);
+        let body = format!("{:0>width$}", body, width = digits + 1);
+        let (int, frac) = body.split_at(body.len() - digits);
+        result.push_str(int);
+        if digits > 0 {
+            result.push('.');
+            result.push_str(frac);
+        }
+        result
     }
 
-    fn fract(&self) -> Self {
-        unimplemented!()
+    /// Split the number into the integral part and the remainder (the mantissa
+    /// of the fractional part, which shares the exponent with the number).
+    pub(crate) fn split_integer(&self) -> (IBig, IBig) {
+        if self.exponent >= 0 {
+            let mut int = self.mantissa.clone();
+            shl_radix::<E>(&mut int, self.exponent as usize);
+            (int, ibig!(0))
+        } else {
+            shr_rem_radix::<E>(&self.mantissa, self.exponent.unsigned_abs())
+        }
     }
 }
 
 /// Multi-precision float number with binary exponent
 #[allow(non_upper_case_globals)]
-pub type BinaryRepr<const Rounding: u8> = FloatRepr<2, Rounding>;
+pub type BinaryRepr<const Rounding: u8> = FloatRepr<2, {Rounding}>;
 /// Multi-precision decimal number with decimal exponent
 #[allow(non_upper_case_globals)]
-pub type DecimalRepr<const Rounding: u8> = FloatRepr<10, Rounding>;
+pub type DecimalRepr<const Rounding: u8> = FloatRepr<10, {Rounding}>;
@@ -0,0 +1,95 @@
+use ibig::{IBig, ibig};
+use crate::{
+    repr::FloatRepr,
+    approx::{Approximation, RoundingDirection},
+    context::{Context, Signal},
+    error::Error,
+    utils::shl_radix,
+    ibig_ext::{sqrt, magnitude}
+};
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the square root of the number
+    /// 
+    /// # Panics
+    /// If the number is negative
+    #[inline]
+    pub fn sqrt(&self) -> Self {
+        self.sqrt_approx().value()
+    }
+
+    /// Calculate the square root of the number, and report whether the root is exact
+    /// and the direction of the rounding if it's not.
+    /// 
+    /// # Panics
+    /// If the number is negative
+    #[inline]
+    pub fn sqrt_approx(&self) -> Approximation<Self, RoundingDirection> {
+        self.sqrt_with_mode(R)
+    }
+
+    /// Calculate the square root of the number, returning an error instead of panicking
+    /// if the number is negative or the exponent overflows
+    pub fn checked_sqrt(&self) -> Result<Self, Error> {
+        if self.mantissa < ibig!(0) {
+            return Err(Error::Domain);
+        }
+        // the mantissa is scaled to have about twice the digits of the precision
+        self.check_exponent_range(2 * self.precision + 3, 0)?;
+        Ok(self.try_sqrt_with_mode(R)?.value())
+    }
+
+    #[inline]
+    pub(crate) fn sqrt_with_mode(&self, mode: u8) -> Approximation<Self, RoundingDirection> {
+        Context::expect_in_range(self.try_sqrt_with_mode(mode))
+    }
+
+    /// Calculate the square root limited to the thread context (see [Context::bounded])
+    pub(crate) fn try_sqrt_with_mode(&self, mode: u8) -> Result<Approximation<Self, RoundingDirection>, Signal> {
+        assert!(self.mantissa >= ibig!(0), "square root of negative number");
+        if self.mantissa == ibig!(0) {
+            return Ok(Approximation::Exact(self.clone()));
+        }
+        Context::bounded(self.precision, mode, |precision, mode| self.sqrt_to_precision(precision, mode))
+    }
+
+    fn sqrt_to_precision(&self, precision: usize, mode: u8) -> Approximation<Self, RoundingDirection> {
+        // scale the mantissa so that the exponent is even and the root has at least precision + 1 digits
+        let mut shift = (2 * precision + 2).saturating_sub(self.actual_precision());
+        if (self.exponent - shift as isize) % 2 != 0 {
+            shift += 1;
+        }
+        let mut scaled = self.mantissa.clone();
+        shl_radix::<E>(&mut scaled, shift);
+
+        let scaled = magnitude(&scaled);
+        let root = sqrt(&scaled);
+        let square = &root * &root;
+        let exact = square == scaled;
+        // the fraction of the root is above a half iff root^2 + root < scaled
+        let above_half = square + &root < scaled;
+        let mut mantissa = IBig::from(root);
+        let mut exponent = (self.exponent - shift as isize) / 2;
+        if !exact {
+            // append a sticky digit on the same side of a half as the fraction, so that the
+            // root can be correctly rounded even if a half is not a digit in the radix
+            shl_radix::<E>(&mut mantissa, 1);
+            mantissa += if above_half { E - 1 } else { 1 };
+            exponent -= 1;
+        }
+        Self::from_parts(mantissa, exponent).with_precision_mode_approx(precision, mode)
+    }
+
+    /// Calculate `sqrt(self^2 + other^2)` without overflow or underflow of the intermediate results
+    ///
+    /// The result is correctly rounded, and its precision is the larger one of the two operands.
+    pub fn hypot(&self, other: &Self) -> Self {
+        // the sum keeps enough digits so that rounding the root is not affected by the truncation
+        let precision = self.precision.max(other.precision);
+        let (mantissa, exponent) = Self::sum_of_squares_truncated(&[self, other], 2 * precision + 4);
+        if mantissa == ibig!(0) {
+            return Self::from_integer(mantissa, precision);
+        }
+        Self { mantissa, exponent, precision }.sqrt()
+    }
+}
@@ -0,0 +1,316 @@
+//! Numbers that preserve their scale (quantum), following the preferred exponents of IEEE 754-2008
+
+use core::{
+    fmt::{self, Display, Formatter, Write},
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+use ibig::{IBig, ibig, error::ParseError};
+use crate::{
+    repr::FloatRepr,
+    approx::{Approximation, RoundingDirection},
+    error::Error,
+    fmt::digits,
+    utils::{shl_radix, get_precision},
+};
+
+/// A float number that remembers its quantum `radix^exponent`, so that numbers of the same value
+/// but written with different trailing zeros (a cohort in IEEE 754-2008, e.g. `1.5` and `1.50`)
+/// can be distinguished, which is not possible with the normalized [FloatRepr].
+///
+/// The value is stored as a [FloatRepr], and the arithmetic operations have the same precision
+/// and rounding as on [FloatRepr]. The exponent of the result is chosen by the rules of IEEE 754-2008:
+/// an exact result uses the exponent closest to the preferred exponent of the operation
+/// (the smaller exponent for addition and subtraction, the sum of the exponents for multiplication,
+/// the difference for division and half for square root), which is possible within the precision;
+/// an inexact result uses all the digits of the precision.
+///
+/// The comparison and hashing only depend on the value, use [Self::same_quantum] to compare the exponents.
+#[derive(Clone, Debug)]
+pub struct ScaledRepr<const E: usize, const R: u8> {
+    value: FloatRepr<E, R>,
+    exponent: isize,
+}
+
+impl<const E: usize, const R: u8> ScaledRepr<E, R> {
+    /// The minimum precision of the numbers created by [Self::from_parts] and parsed from strings,
+    /// which is the default precision of [Context](crate::Context). Without it a short literal
+    /// such as `2` or `0.00` would have a precision of one digit or zero, and an inexact
+    /// square root or quotient of it would be rounded to that.
+    pub const MIN_PRECISION: usize = 28;
+
+    /// Create a number from the coefficient and the exponent, the trailing zeros of the
+    /// coefficient are kept, and the precision is the number of digits in the coefficient
+    /// but at least [Self::MIN_PRECISION].
+    pub fn from_parts(coefficient: IBig, exponent: isize) -> Self {
+        let precision = get_precision::<E>(&coefficient).max(Self::MIN_PRECISION);
+        Self { value: FloatRepr::from_parts(coefficient, exponent).with_min_precision(precision), exponent }
+    }
+
+    /// Get the coefficient and the exponent of the number, where the coefficient
+    /// keeps the trailing zeros, i.e. `value = coefficient * radix^exponent`.
+    pub fn into_parts(self) -> (IBig, isize) {
+        let mut coefficient = self.value.mantissa;
+        if coefficient != ibig!(0) {
+            shl_radix::<E>(&mut coefficient, (self.value.exponent - self.exponent) as usize);
+        }
+        (coefficient, self.exponent)
+    }
+
+    /// Get the exponent of the quantum (the unit in the last place) of the number
+    #[inline]
+    pub fn exponent(&self) -> isize {
+        self.exponent
+    }
+
+    /// Get the precision of the number
+    #[inline]
+    pub fn precision(&self) -> usize {
+        self.value.precision
+    }
+
+    /// Get the value of the number, which doesn't keep the quantum
+    #[inline]
+    pub fn value(&self) -> &FloatRepr<E, R> {
+        &self.value
+    }
+
+    /// Convert into the value of the number, which doesn't keep the quantum
+    #[inline]
+    pub fn into_value(self) -> FloatRepr<E, R> {
+        self.value
+    }
+
+    /// Check whether two numbers have the same exponent (sameQuantum in IEEE 754-2008)
+    #[inline]
+    pub fn same_quantum(&self, other: &Self) -> bool {
+        self.exponent == other.exponent
+    }
+
+    /// Remove the trailing zeros of the coefficient (reduce in IEEE 754-2008)
+    #[inline]
+    pub fn normalize(self) -> Self {
+        self.value.into()
+    }
+
+    /// Change the precision of the number, the exponent is kept if the value is not rounded
+    /// and the coefficient fits in the new precision.
+    #[inline]
+    pub fn with_precision(self, precision: usize) -> Self {
+        Self::from_approx(self.value.with_precision_mode_approx(precision, R), self.exponent)
+    }
+
+    /// Round the number to have exactly `scale` digits after the radix point (quantize in
+    /// IEEE 754-2008 with the exponent `-scale`). The precision is grown if the coefficient needs
+    /// more digits.
+    pub fn quantize(&self, scale: isize) -> Self {
+        let exponent = -scale;
+        let value = self.value.clone().with_exponent_mode_approx(exponent, R).value();
+        let digits = if value.mantissa == ibig!(0) {
+            0
+        } else {
+            (value.log_radix_floor() - exponent + 1) as usize
+        };
+        Self { value: value.with_min_precision(digits), exponent }
+    }
+
+    /// Calculate the square root of the number, with the preferred exponent `floor(exponent / 2)`
+    ///
+    /// # Panics
+    /// If the number is negative
+    #[inline]
+    pub fn sqrt(&self) -> Self {
+        Self::from_approx(self.value.sqrt_with_mode(R), self.exponent.div_euclid(2))
+    }
+
+    /// Select the exponent of the result of an operation with the given preferred exponent
+    fn from_approx(result: Approximation<FloatRepr<E, R>, RoundingDirection>, preferred: isize) -> Self {
+        let inexact = !result.is_exact();
+        let value = result.value();
+        if value.mantissa == ibig!(0) {
+            return Self { value, exponent: preferred };
+        }
+
+        // the exponent of the lowest digit if all the digits of the precision are used
+        let full = (value.log_radix_floor() - value.precision as isize + 1).min(value.exponent);
+        let exponent = if inexact {
+            full
+        } else {
+            preferred.clamp(full, value.exponent)
+        };
+        Self { value, exponent }
+    }
+}
+
+impl<const E: usize, const R: u8> From<FloatRepr<E, R>> for ScaledRepr<E, R> {
+    /// Convert a float number into a [ScaledRepr] without trailing zeros
+    #[inline]
+    fn from(value: FloatRepr<E, R>) -> Self {
+        let exponent = value.exponent;
+        Self { value, exponent }
+    }
+}
+
+impl<const E: usize, const R: u8> FromStr for ScaledRepr<E, R> {
+    type Err = ParseError;
+
+    /// Convert a string in a given base to [ScaledRepr], the format is the same as [FloatRepr],
+    /// and the exponent is decided by the digits as they are written (e.g. `1.50` has exponent -2).
+    /// The precision is the same as [ScaledRepr::from_parts].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Radix` is not supported by [ibig] (currently only 2 ~ 36 is supported),
+    /// or the exponent overflows.
+    fn from_str(src: &str) -> Result<Self, ParseError> {
+        match FloatRepr::<E, R>::parse_parts(src) {
+            Ok((coefficient, exponent)) => Ok(Self::from_parts(coefficient, exponent)),
+            Err(Error::Parse(e)) => Err(e),
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+impl<const E: usize, const R: u8> Display for ScaledRepr<E, R> {
+    /// Print the number with all the digits of the coefficient. Decimal numbers are printed
+    /// in scientific notation if the exponent is positive or the number is very small,
+    /// following the to-scientific-string conversion in the General Decimal Arithmetic Specification.
+    ///
+    /// If the precision of the formatter is given, the number is quantized to that many digits
+    /// after the radix point before printing.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(scale) = f.precision() {
+            if self.exponent != -(scale as isize) {
+                return write!(f, "{}", self.quantize(scale as isize));
+            }
+        }
+
+        let (coefficient, exponent) = self.clone().into_parts();
+        if coefficient < ibig!(0) {
+            f.write_char('-')?;
+        }
+        let digits = digits::<E>(&coefficient);
+        let adjusted = exponent + digits.len() as isize - 1;
+
+        if E == 10 && (exponent > 0 || adjusted < -6) {
+            // scientific notation with one digit before the point
+            let (lead, rest) = digits.split_at(1);
+            f.write_str(lead)?;
+            if !rest.is_empty() {
+                f.write_char('.')?;
+                f.write_str(rest)?;
+            }
+            write!(f, "E{:+}", adjusted)
+        } else if exponent >= 0 {
+            f.write_str(&digits)?;
+            for _ in 0..exponent {
+                f.write_char('0')?;
+            }
+            Ok(())
+        } else {
+            // pad the coefficient with leading zeros so that there's a digit before the point
+            let scale = exponent.unsigned_abs();
+            let digits = format!("{:0>width$}", digits, width = scale + 1);
+            let (int, frac) = digits.split_at(digits.len() - scale);
+            f.write_str(int)?;
+            f.write_char('.')?;
+            f.write_str(frac)
+        }
+    }
+}
+
+impl<const E: usize, const R: u8> PartialEq for ScaledRepr<E, R> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<const E: usize, const R: u8> Eq for ScaledRepr<E, R> {}
+
+impl<const E: usize, const R: u8> PartialOrd for ScaledRepr<E, R> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const E: usize, const R: u8> Ord for ScaledRepr<E, R> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<const E: usize, const R: u8> core::hash::Hash for ScaledRepr<E, R> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<const E: usize, const R: u8> Neg for ScaledRepr<E, R> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self { value: -self.value, exponent: self.exponent }
+    }
+}
+
+impl<const E: usize, const R: u8> Neg for &ScaledRepr<E, R> {
+    type Output = ScaledRepr<E, R>;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self.clone().neg()
+    }
+}
+
+impl<const E: usize, const R: u8> Add for &ScaledRepr<E, R> {
+    type Output = ScaledRepr<E, R>;
+    fn add(self, rhs: Self) -> Self::Output {
+        let result = self.value.clone().add_with_mode(rhs.value.clone(), R);
+        ScaledRepr::from_approx(result, self.exponent.min(rhs.exponent))
+    }
+}
+
+impl<const E: usize, const R: u8> Sub for &ScaledRepr<E, R> {
+    type Output = ScaledRepr<E, R>;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.add(&-rhs)
+    }
+}
+
+impl<const E: usize, const R: u8> Mul for &ScaledRepr<E, R> {
+    type Output = ScaledRepr<E, R>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let result = self.value.mul_with_mode(&rhs.value, R);
+        let preferred = self.exponent.checked_add(rhs.exponent).expect("exponent overflow");
+        ScaledRepr::from_approx(result, preferred)
+    }
+}
+
+impl<const E: usize, const R: u8> Div for &ScaledRepr<E, R> {
+    type Output = ScaledRepr<E, R>;
+    fn div(self, rhs: Self) -> Self::Output {
+        let result = self.value.clone().div_with_mode(rhs.value.clone(), R);
+        let preferred = self.exponent.checked_sub(rhs.exponent).expect("exponent overflow");
+        ScaledRepr::from_approx(result, preferred)
+    }
+}
+
+macro_rules! impl_owned_binop {
+    ($trait:ident, $method:ident) => {
+        impl<const E: usize, const R: u8> $trait for ScaledRepr<E, R> {
+            type Output = Self;
+            #[inline]
+            fn $method(self, rhs: Self) -> Self::Output {
+                (&self).$method(&rhs)
+            }
+        }
+    };
+}
+impl_owned_binop!(Add, add);
+impl_owned_binop!(Sub, sub);
+impl_owned_binop!(Mul, mul);
+impl_owned_binop!(Div, div);
@@ -1,7 +1,57 @@
 use core::ops::Neg;
-use ibig::ops::Abs;
+use ibig::{ibig, ops::Abs};
 use crate::repr::FloatRepr;
 
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Get the absolute value of the number
+    #[inline]
+    pub fn abs(&self) -> Self {
+        Self { mantissa: Abs::abs(self.mantissa.clone()), exponent: self.exponent, precision: self.precision }
+    }
+
+    /// Get a number that represents the sign of the number: 1 if positive, -1 if negative and 0 if zero
+    #[inline]
+    pub fn signum(&self) -> Self {
+        Self::from_integer(self.mantissa.signum(), self.precision)
+    }
+
+    /// Returns true if the number is zero or positive. (there is no negative zero)
+    #[inline]
+    pub fn is_sign_positive(&self) -> bool {
+        self.mantissa >= ibig!(0)
+    }
+
+    /// Returns true if the number is negative. (there is no negative zero)
+    #[inline]
+    pub fn is_sign_negative(&self) -> bool {
+        self.mantissa < ibig!(0)
+    }
+
+    /// Get a number with the magnitude of `self` and the sign of `sign`,
+    /// zero is considered as positive.
+    #[inline]
+    pub fn copysign(&self, sign: &Self) -> Self {
+        if self.is_sign_negative() == sign.is_sign_negative() {
+            self.clone()
+        } else {
+            -self
+        }
+    }
+
+    /// Returns true if the number is zero
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == ibig!(0)
+    }
+
+    /// Returns true if the number is an integer
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        // the representation is normalized, so the exponent of a fraction is always negative
+        self.exponent >= 0 || self.is_zero()
+    }
+}
+
 impl<const E: usize, const R: u8> Neg for FloatRepr<E, R> {
     type Output = Self;
     fn neg(mut self) -> Self::Output {
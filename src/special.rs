@@ -0,0 +1,533 @@
+//! Implementation of special functions: Riemann zeta, polylogarithm and Lambert W
+
+use core::f64::consts::PI;
+use core::convert::TryInto;
+use ibig::{IBig, ibig, ubig, ops::{RemEuclid, UnsignedAbs}};
+use crate::{repr::FloatRepr, context::Context, utils::{get_precision, shl_radix, newton_schedule}};
+
+/// Calculate the even Bernoulli numbers `[B_2, B_4, ..., B_2n]` as (numerator, denominator) pairs.
+///
+/// The numbers are derived from the tangent numbers `T_k` (which are integers) by
+/// `B_2k = (-1)^(k-1) 2k T_k / (4^k (4^k - 1))`, see Brent & Harvey (2011).
+fn bernoulli_even(n: usize) -> Vec<(IBig, IBig)> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut tangent = vec![ibig!(0); n + 1];
+    tangent[1] = ibig!(1);
+    for k in 2..=n {
+        tangent[k] = &tangent[k - 1] * IBig::from(k - 1);
+    }
+    for k in 2..=n {
+        for j in k..=n {
+            tangent[j] = &tangent[j - 1] * IBig::from(j - k) + &tangent[j] * IBig::from(j - k + 2);
+        }
+    }
+
+    (1..=n).map(|k| {
+        let pow4 = ibig!(1) << (2 * k);
+        let mut num = IBig::from(2 * k) * &tangent[k];
+        if k % 2 == 0 {
+            num = -num;
+        }
+        (num, &pow4 * (&pow4 - 1u8))
+    }).collect()
+}
+
+/// Calculate `n!` as an integer
+fn factorial(n: usize) -> IBig {
+    (2..=n).fold(ibig!(1), |acc, k| acc * IBig::from(k))
+}
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Calculate the Riemann zeta function `ζ(s)` for a real argument s
+    ///
+    /// # Panics
+    /// If the argument is 1 (where the function has a pole)
+    pub fn zeta(&self) -> Self {
+        Context::bounded_value(|| self.zeta_unbounded())
+    }
+
+    fn zeta_unbounded(&self) -> Self {
+        let precision = self.precision;
+        let s = self.to_f64_estimate();
+
+        // exact values for integer arguments
+        if self.exponent >= 0 || self.mantissa == ibig!(0) {
+            let n: Option<isize> = self.to_int().try_into().ok();
+            match n {
+                Some(1) => panic!("zeta function has a pole at 1"),
+                Some(0) => return Self::from_ratio(ibig!(-1), ibig!(2), precision),
+                Some(n) if n < 0 && n % 2 == 0 => return Self::from_integer(ibig!(0), precision),
+                Some(n) if n < 0 => {
+                    // ζ(-m) = -B_{m+1} / (m+1) for odd m
+                    let m = n.unsigned_abs();
+                    let (num, den) = bernoulli_even(m.div_ceil(2)).pop().unwrap();
+                    return Self::from_ratio(-num, den * IBig::from(m + 1), precision);
+                },
+                Some(n) if n % 2 == 0 && (n as f64) < precision as f64 * (E as f64).log2() => {
+                    // ζ(2k) = k T_k pi^(2k) / ((4^k - 1) (2k)!)
+                    let k = n as usize / 2;
+                    let (num, den) = bernoulli_even(k).pop().unwrap();
+                    let num = num.unsigned_abs() * (ubig!(1) << (2 * k - 1));
+                    let wp = precision + get_precision::<E>(&IBig::from(n)) + 2;
+                    let ratio = Self::from_ratio(IBig::from(num), den * factorial(2 * k), wp);
+                    let pi_pow = Self::pi(wp).powi(2 * k);
+                    return (ratio * pi_pow).with_precision(precision);
+                },
+                _ => {}
+            };
+        }
+
+        // ζ(s) = 1 + 2^-s + ..., so it rounds to one if s is large enough
+        if s > (precision + 2) as f64 * (E as f64).log2() + 1. {
+            return Self::one_with_tiny(precision, false).with_precision(precision);
+        }
+
+        // the direct summation cancels about (1 - s) log_radix(N) digits for negative s
+        if s < -1. {
+            return self.zeta_reflection(precision);
+        }
+        self.zeta_euler_maclaurin(precision)
+    }
+
+    /// Evaluate the zeta function for s < -1 using the functional equation
+    /// `ζ(s) = 2^s pi^(s-1) sin(pi s/2) Γ(1-s) ζ(1-s)`, where every factor is evaluated without cancellation.
+    ///
+    /// The factors `2^s pi^(s-1) Γ(1-s)` are combined into one exponential, whose argument is
+    /// about `(1-s) ln(1-s)`, so its absolute error becomes the relative error of the result.
+    fn zeta_reflection(&self, precision: usize) -> Self {
+        let t_est = 1. - self.to_f64_estimate();
+        let magnitude = (t_est * (t_est.ln() + 3.)) as usize + 1;
+        let wp = precision + get_precision::<E>(&IBig::from(magnitude)) + 3;
+
+        // t = 1 - s is exact, since s is not an integer it has a fractional part
+        let t = (-self.sub_one_exact()).with_precision(wp);
+        let s = self.clone().with_precision(wp);
+        let one = Self::from_integer(ibig!(1), wp);
+        let ln2 = Self::from_integer(ibig!(2), wp).ln();
+        let ln_pi = Self::pi(wp).ln();
+        let log = &s * &ln2 + (&s - &one) * ln_pi + t.ln_gamma(wp);
+
+        let zeta = t.clone().with_precision(precision + 2).zeta().with_precision(wp);
+        (log.exp() * self.sin_half_pi(wp) * zeta).with_precision(precision)
+    }
+
+    /// Evaluate `ln Γ(x)` for x > 1 with absolute error about `radix^-wp`, using the Stirling series
+    /// `ln Γ(z) = (z - 1/2) ln z - z + ln(2 pi)/2 + sum_{j=1}^M B_2j / (2j (2j-1) z^(2j-1))`
+    /// after shifting the argument with `Γ(x) = Γ(x + N) / (x (x+1) ... (x+N-1))`.
+    ///
+    /// The smallest term of the series is about `exp(-2 pi z)`, so z is shifted to at least `wp ln(radix) / 2pi`.
+    fn ln_gamma(&self, wp: usize) -> Self {
+        let ln_e = (E as f64).ln();
+        let x_est = self.to_f64_estimate();
+        let shift = (wp as f64 * ln_e / (2. * PI) + 1. - x_est).max(0.).ceil() as usize;
+        let guard = get_precision::<E>(&IBig::from(shift + 1)) + 2;
+        let wp_guard = wp + guard;
+
+        let x = self.clone().with_precision(wp_guard);
+        let mut product = Self::from_integer(ibig!(1), wp_guard);
+        for k in 0..shift {
+            product = product * (&x + &Self::from_integer(IBig::from(k), wp_guard));
+        }
+        let z = &x + &Self::from_integer(IBig::from(shift), wp_guard);
+
+        // the number of terms needed, where |B_2j| ~ 2 (2j)! / (2 pi)^(2j)
+        let z_est = x_est + shift as f64;
+        let (mut m, mut ln_fact) = (0, 0.);
+        loop {
+            m += 1;
+            ln_fact += ((2 * m - 1) as f64).ln() + ((2 * m) as f64).ln();
+            let ln_term = 2f64.ln() + ln_fact - (2 * m) as f64 * (2. * PI).ln()
+                - ((2 * m) as f64 * (2 * m - 1) as f64).ln() - (2 * m - 1) as f64 * z_est.ln();
+            if ln_term < -(wp_guard as f64) * ln_e {
+                break;
+            }
+        }
+
+        let half = Self::from_ratio(ibig!(1), ibig!(2), wp_guard);
+        let ln_z = z.ln();
+        let ln_2pi = (Self::pi(wp_guard) * Self::from_integer(ibig!(2), wp_guard)).ln();
+        let mut sum = (&z - &half) * ln_z - z.clone() + &ln_2pi * &half;
+
+        let z2 = &z * &z;
+        let mut z_pow = z.clone();
+        for (j, (num, den)) in bernoulli_even(m).into_iter().enumerate() {
+            let j = j + 1;
+            let coeff = Self::from_ratio(num, den * IBig::from(2 * j * (2 * j - 1)), wp_guard);
+            sum = sum + coeff / z_pow.clone();
+            z_pow = &z_pow * &z2;
+        }
+        (sum - product.ln()).with_precision(wp)
+    }
+
+    /// Evaluate `sin(pi s / 2)` for a non-integer s with relative error about `radix^-wp`.
+    ///
+    /// The argument is reduced exactly with `s = 4k + q + f` (where q is 0..3 and 0 < f < 1)
+    /// to `±sin(pi g / 2)` with `g = f` or `g = 1 - f`, then the Taylor series is summed.
+    fn sin_half_pi(&self, wp: usize) -> Self {
+        let (mut int, mut rem) = self.split_integer();
+        let mut unit = ibig!(1);
+        shl_radix::<E>(&mut unit, self.exponent.unsigned_abs());
+        if rem < ibig!(0) {
+            int -= 1u8;
+            rem += &unit;
+        }
+
+        let q = int.rem_euclid(ibig!(4));
+        let g = if q == ibig!(1) || q == ibig!(3) { unit - rem } else { rem };
+        let g = Self::from_parts(g, self.exponent).with_precision(wp);
+        let x = Self::pi(wp) * g / Self::from_integer(ibig!(2), wp);
+
+        let x2 = &x * &x;
+        let mut sum = x.clone();
+        let mut term = x;
+        let mut k = 1usize;
+        loop {
+            term = -(&term * &x2) / Self::from_integer(IBig::from((2 * k) * (2 * k + 1)), wp);
+            if term.log_radix_floor() < sum.log_radix_floor() - wp as isize {
+                break;
+            }
+            sum = sum + term.clone();
+            k += 1;
+        }
+        if q >= ibig!(2) { -sum } else { sum }
+    }
+
+    /// Evaluate the zeta function using the Euler-Maclaurin summation
+    /// `ζ(s) = sum_{k<N} k^-s + N^(1-s)/(s-1) + N^-s/2 + sum_{j=1}^M B_2j/(2j)! s(s+1)...(s+2j-2) N^(-s-2j+1)`.
+    ///
+    /// The correction terms decrease like `((|s| + 2j) / (2 pi N))^2j`, so the truncation
+    /// error is small enough if `N >= |s| + 2M` and `(2 pi)^(-2M) < radix^-precision`.
+    fn zeta_euler_maclaurin(&self, precision: usize) -> Self {
+        let ln_e = (E as f64).ln();
+        let s_est = self.to_f64_estimate();
+
+        // for negative s, the terms are as large as N^(1-s) and they cancel to a much smaller result,
+        // while N depends on the working precision, so the estimation of cancellation is refined once
+        let terms = |digits: usize| {
+            let m = (digits as f64 * ln_e / (2. * (2. * PI).ln())) as usize + 1;
+            (m, 2 * m + s_est.abs().ceil() as usize + 1)
+        };
+        let cancel = |n: usize| if s_est < 0. {
+            ((1. - s_est) * (n as f64).ln() / ln_e) as usize + 1
+        } else {
+            0
+        };
+        let (_, n) = terms(precision + 3);
+        let (_, n) = terms(precision + cancel(n) + 3);
+        let extra = cancel(n);
+        let (m, n) = terms(precision + extra + 3);
+        let wp = precision + extra + get_precision::<E>(&IBig::from(n)) + 3;
+        let s = self.clone().with_precision(wp);
+
+        // calculate k^-s for k <= N, the power only needs to be evaluated for primes
+        let int_s: Option<usize> = if self.exponent >= 0 { self.to_int().try_into().ok() } else { None };
+        let mut factor = vec![0usize; n + 1];
+        let mut powers: Vec<Self> = Vec::with_capacity(n + 1);
+        powers.push(Self::from_integer(ibig!(0), wp));
+        powers.push(Self::from_integer(ibig!(1), wp));
+        for k in 2..=n {
+            if factor[k] == 0 {
+                for multiple in (k..=n).step_by(k) {
+                    if factor[multiple] == 0 {
+                        factor[multiple] = k;
+                    }
+                }
+            }
+
+            let p = factor[k];
+            let power = if p != k {
+                &powers[p] * &powers[k / p]
+            } else if let Some(int_s) = int_s {
+                Self::from_ratio(ibig!(1), IBig::from(k).pow(int_s), wp)
+            } else {
+                (-(&s * &Self::from_integer(IBig::from(k), wp).ln())).exp()
+            };
+            powers.push(power);
+        }
+
+        let mut sum = Self::from_integer(ibig!(0), wp);
+        for power in &powers[1..n] {
+            sum = sum + power.clone();
+        }
+
+        // tail terms N^(1-s)/(s-1) + N^-s/2
+        let one = Self::from_integer(ibig!(1), wp);
+        let two = Self::from_integer(ibig!(2), wp);
+        let big_n = Self::from_integer(IBig::from(n), wp);
+        let n_pow = powers[n].clone();
+        sum = sum + &n_pow * &big_n / (&s - &one) + &n_pow / &two;
+
+        // correction terms, where c_j = s(s+1)...(s+2j-2) / (2j)! * N^(-s-2j+1)
+        let n2 = &big_n * &big_n;
+        let mut c = &s * &n_pow / (&two * &big_n);
+        for (j, (num, den)) in bernoulli_even(m).into_iter().enumerate() {
+            let j = j + 1;
+            sum = sum + &c * &Self::from_ratio(num, den, wp);
+
+            let j_num = (&s + &Self::from_integer(IBig::from(2 * j - 1), wp)) * (&s + &Self::from_integer(IBig::from(2 * j), wp));
+            let j_den = Self::from_integer(IBig::from((2 * j + 1) * (2 * j + 2)), wp) * n2.clone();
+            c = &c * &j_num / j_den;
+        }
+        sum.with_precision(precision)
+    }
+
+    /// Calculate the polylogarithm `Li_n(x) = sum_{k>=1} x^k / k^n` of integer order n
+    ///
+    /// # Panics
+    /// - If x is 1 and n <= 1 (where the function diverges)
+    /// - If n > 0 and x > 1 (where the function has complex values)
+    pub fn polylog(&self, n: isize) -> Self {
+        Context::bounded_value(|| self.polylog_unbounded(n))
+    }
+
+    fn polylog_unbounded(&self, n: isize) -> Self {
+        let precision = self.precision;
+        if self.mantissa == ibig!(0) {
+            return self.clone();
+        }
+        if n <= 0 {
+            return self.polylog_rational(n.unsigned_abs());
+        }
+
+        let d = self.sub_one_exact();
+        assert!(d.mantissa <= ibig!(0), "polylogarithm is complex for x > 1");
+        if d.mantissa == ibig!(0) {
+            assert!(n > 1, "polylogarithm diverges at 1 for order 1");
+            return Self::from_integer(IBig::from(n), precision).zeta();
+        }
+
+        let wp = precision + 3;
+        let result = if n == 1 {
+            // Li_1(x) = -ln(1 - x)
+            -(-d).with_precision(wp).ln()
+        } else {
+            self.clone().with_precision(wp).polylog_real(n as usize)
+        };
+        result.with_precision(precision)
+    }
+
+    /// Evaluate the polylogarithm of order n >= 2 for x <= 1
+    fn polylog_real(&self, n: usize) -> Self {
+        let wp = self.precision;
+        if self.exponent == 0 && (self.mantissa == ibig!(1) || self.mantissa == ibig!(-1)) {
+            // Li_n(1) = ζ(n) and Li_n(-1) = -(1 - 2^(1-n)) ζ(n), where the expansion around ln(x) breaks down
+            let zeta = Self::from_integer(IBig::from(n), wp).zeta();
+            if self.mantissa == ibig!(1) {
+                return zeta;
+            }
+            let one = Self::from_integer(ibig!(1), wp);
+            return -(&one - &Self::from_ratio(ibig!(1), ibig!(1) << (n - 1), wp)) * zeta;
+        }
+
+        let x = self.to_f64_estimate();
+        if x.abs() <= 0.5 || n as f64 > wp as f64 * (E as f64).log2() {
+            self.polylog_series(n)
+        } else if x > 0. {
+            self.polylog_near_one(n)
+        } else if x >= -1. {
+            // duplication formula: Li_n(x) = 2^(1-n) Li_n(x^2) - Li_n(-x)
+            let x2 = (self * self).polylog_real(n);
+            let scale = Self::from_ratio(ibig!(1), ibig!(1) << (n - 1), wp);
+            scale * x2 - (-self).polylog_real(n)
+        } else {
+            self.polylog_inversion(n)
+        }
+    }
+
+    /// Evaluate `Li_n(x) = sum_{k>=1} x^k / k^n` directly, which converges fast for |x| <= 1/2
+    fn polylog_series(&self, n: usize) -> Self {
+        let wp = self.precision;
+        let mut sum = self.clone();
+        let mut power = self.clone();
+        let mut k = 2usize;
+        loop {
+            power = &power * self;
+            let term = &power / &Self::from_integer(IBig::from(k).pow(n), wp);
+            if term.mantissa == ibig!(0) || term.log_radix_floor() < sum.log_radix_floor() - wp as isize {
+                break sum;
+            }
+            sum = sum + term;
+            k += 1;
+        }
+    }
+
+    /// Evaluate the polylogarithm for 1/2 < x < 1 using the expansion around `mu = ln(x)`:
+    /// `Li_n(e^mu) = mu^(n-1)/(n-1)! (H_{n-1} - ln(-mu)) + sum_{k != n-1} ζ(n-k) mu^k / k!`
+    fn polylog_near_one(&self, n: usize) -> Self {
+        let wp = self.precision;
+        let mu = self.ln();
+
+        // terms decrease like (|mu| / 2pi)^k when k >= n, and |mu| < ln(2)
+        let max_k = n + (wp as f64 * (E as f64).ln() / (2. * PI / 2f64.ln()).ln()) as usize + 2;
+        let bernoulli = bernoulli_even(max_k.saturating_sub(n) / 2 + 1);
+
+        let mut sum = Self::from_integer(ibig!(0), wp);
+        let mut power = Self::from_integer(ibig!(1), wp); // mu^k / k!
+        for k in 0..=max_k {
+            if k > 0 {
+                power = &power * &mu / Self::from_integer(IBig::from(k), wp);
+            }
+
+            let coeff = if k + 1 < n {
+                Self::from_integer(IBig::from(n - k), wp).zeta()
+            } else if k + 1 == n {
+                // H_{n-1} - ln(-mu)
+                let harmonic = (1..n).fold(Self::from_integer(ibig!(0), wp), |acc, i| {
+                    acc + Self::from_ratio(ibig!(1), IBig::from(i), wp)
+                });
+                harmonic - (-&mu).ln()
+            } else if k == n {
+                Self::from_ratio(ibig!(-1), ibig!(2), wp)
+            } else if (k - n).is_multiple_of(2) {
+                continue; // ζ of negative even integers are zero
+            } else {
+                // ζ(-m) = -B_{m+1} / (m+1)
+                let m = k - n;
+                let (num, den) = bernoulli[m.div_ceil(2) - 1].clone();
+                Self::from_ratio(-num, den * IBig::from(m + 1), wp)
+            };
+
+            let term = &coeff * &power;
+            if k > n && (term.mantissa == ibig!(0) || term.log_radix_floor() < sum.log_radix_floor() - wp as isize) {
+                break;
+            }
+            sum = sum + term;
+        }
+        sum
+    }
+
+    /// Evaluate the polylogarithm for x < -1 using the inversion formula, with t = -x, L = ln(t):
+    /// `Li_n(-t) = (-1)^(n-1) Li_n(-1/t) - L^n/n! + 2 sum_{k=1}^{n/2} L^(n-2k)/(n-2k)! Li_2k(-1)`,
+    /// where `Li_2k(-1) = -(1 - 2^(1-2k)) ζ(2k)`
+    fn polylog_inversion(&self, n: usize) -> Self {
+        let wp = self.precision;
+        let t = -self;
+        let l = t.ln();
+        let one = Self::from_integer(ibig!(1), wp);
+
+        let mut sum = (-(&one / &t)).polylog_real(n);
+        if n.is_multiple_of(2) {
+            sum = -sum;
+        }
+        sum = sum - l.powi(n) / Self::from_integer(factorial(n), wp);
+        for k in 1..=n / 2 {
+            let zeta = Self::from_integer(IBig::from(2 * k), wp).zeta();
+            let li = -(&one - &Self::from_ratio(ibig!(1), ibig!(1) << (2 * k - 1), wp)) * zeta;
+            let coeff = l.powi(n - 2 * k) / Self::from_integer(factorial(n - 2 * k), wp);
+            sum = sum + Self::from_integer(ibig!(2), wp) * coeff * li;
+        }
+        sum
+    }
+
+    /// Evaluate the polylogarithm of order -m exactly using the rational function
+    /// `Li_{-m}(x) = sum_{k=0}^m k! S(m+1, k+1) (x / (1-x))^(k+1)`, where S is the Stirling number of the second kind
+    fn polylog_rational(&self, m: usize) -> Self {
+        // write x = a / b, then x / (1 - x) = a / (b - a)
+        let (a, b) = if self.exponent >= 0 {
+            (self.to_int(), ibig!(1))
+        } else {
+            let mut b = ibig!(1);
+            shl_radix::<E>(&mut b, self.exponent.unsigned_abs());
+            (self.mantissa.clone(), b)
+        };
+        let c = &b - &a;
+        assert!(c != ibig!(0), "polylogarithm diverges at 1 for non-positive orders");
+
+        // Stirling numbers S(m+1, j) for j in 0..=m+1
+        let mut stirling = vec![ibig!(1)];
+        for i in 1..=m + 1 {
+            let mut row = vec![ibig!(0); i + 1];
+            for j in 1..=i {
+                let prev = if j < i { &stirling[j] * IBig::from(j) } else { ibig!(0) };
+                row[j] = prev + &stirling[j - 1];
+            }
+            stirling = row;
+        }
+
+        let mut num = ibig!(0);
+        let mut k_factorial = ibig!(1);
+        for k in 0..=m {
+            if k > 0 {
+                k_factorial *= IBig::from(k);
+            }
+            num += &k_factorial * &stirling[k + 1] * a.pow(k + 1) * c.pow(m - k);
+        }
+        Self::from_ratio(num, c.pow(m + 1), self.precision)
+    }
+
+    /// Calculate the principal branch of the Lambert W function (W_0), i.e.
+    /// the solution of `w e^w = x` with `w >= -1`
+    ///
+    /// # Panics
+    /// If x < -1/e
+    pub fn lambert_w(&self) -> Self {
+        if self.mantissa == ibig!(0) {
+            return self.clone();
+        }
+        Context::bounded_value(|| self.lambert_w_branch(false))
+    }
+
+    /// Calculate the lower branch of the Lambert W function (W_{-1}), i.e.
+    /// the solution of `w e^w = x` with `w <= -1`
+    ///
+    /// # Panics
+    /// If x is not in the range [-1/e, 0)
+    pub fn lambert_w_m1(&self) -> Self {
+        assert!(self.mantissa < ibig!(0), "lower branch of Lambert W is only defined in [-1/e, 0)");
+        Context::bounded_value(|| self.lambert_w_branch(true))
+    }
+
+    fn lambert_w_branch(&self, lower: bool) -> Self {
+        let precision = self.precision;
+        let ln_e = (E as f64).ln();
+
+        // the distance to the branch point q = e x + 1, where the result is about -1 ± sqrt(2q).
+        // the evaluation of q cancels, and the result is sensitive to x near the branch point,
+        // so more digits are required if q is small
+        let branch_dist = |wp: usize| {
+            let one = Self::from_integer(ibig!(1), wp);
+            one.exp() * self.clone().with_precision(wp) + one
+        };
+        let q = branch_dist(precision + 3);
+        assert!(q.mantissa >= ibig!(0), "Lambert W is only defined for x >= -1/e");
+        let q_est = q.to_f64_estimate();
+        let guard = if q_est < 0.5 { (-q_est.ln() / ln_e) as usize + 2 } else { 2 };
+        let q_est = branch_dist(precision + 2 * guard).to_f64_estimate();
+
+        // initial estimation
+        let estimate = if q_est < 0.3 {
+            let p = if lower { -(2. * q_est).sqrt() } else { (2. * q_est).sqrt() };
+            -1. + p - p * p / 3. + 11. / 72. * p * p * p
+        } else if lower || self.ln_f64() > 3f64.ln() {
+            // asymptotic expansion w = L1 - L2 + L2 / L1, where L1 = ln|x|, L2 = ln|L1|
+            let l1 = self.ln_f64();
+            let l2 = l1.abs().ln();
+            l1 - l2 + l2 / l1
+        } else {
+            // approximation by Winitzki (2003)
+            let l = self.to_f64_estimate().ln_1p();
+            l * (1. - l.ln_1p() / (2. + l))
+        };
+        let mut w = if !lower && self.ln_f64() < -10. {
+            self.clone() // W(x) ~ x when x is tiny
+        } else {
+            FloatRepr::<2, R>::from(estimate).with_radix::<E>()
+        };
+
+        // halley iterations w = w - f / (e^w (w + 1) - (w + 2) f / (2w + 2)), where f = w e^w - x
+        let full = precision + guard;
+        for wp in newton_schedule(2 * guard, full) {
+            let one = Self::from_integer(ibig!(1), wp);
+            let w_wp = w.with_precision(wp);
+            let ew = w_wp.exp();
+            let f = &w_wp * &ew - self.clone().with_precision(wp);
+            let w1 = &w_wp + &one;
+            let den = &ew * &w1 - (&w1 + &one) * f.clone() / (&w1 + &w1);
+            w = w_wp - f / den;
+        }
+        w.with_precision(precision)
+    }
+}
@@ -0,0 +1,135 @@
+//! Units in the last place and adjacent numbers at the precision of a number
+
+use core::cmp::Ordering;
+use ibig::{IBig, ibig};
+use crate::{repr::FloatRepr, error::Error, utils::shl_radix};
+
+impl<const E: usize, const R: u8> FloatRepr<E, R> {
+    /// Get `radix^(1 - precision)`, the difference between one and the next larger number
+    /// with the given precision
+    ///
+    /// # Panics
+    /// If the precision is zero, see [Self::checked_epsilon]
+    #[inline]
+    pub fn epsilon(precision: usize) -> Self {
+        Self::checked_epsilon(precision).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get `radix^(1 - precision)` (see [Self::epsilon]), returning an error instead of
+    /// panicking if the precision is zero, where there are no digits to have a lowest one
+    pub fn checked_epsilon(precision: usize) -> Result<Self, Error> {
+        if precision == 0 {
+            return Err(Error::ZeroPrecision);
+        }
+        Ok(Self::from_parts_with_precision(ibig!(1), 1 - precision as isize, precision))
+    }
+
+    /// Get the unit in the last place of the number, i.e. the value of the lowest digit
+    /// when the number is written with all the digits of its precision.
+    ///
+    /// The result is zero if the number is zero (there's no smallest positive number,
+    /// since the exponent is unbounded).
+    ///
+    /// # Panics
+    /// If the precision is zero, see [Self::checked_ulp]
+    #[inline]
+    pub fn ulp(&self) -> Self {
+        self.checked_ulp().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the unit in the last place of the number (see [Self::ulp]), returning an error
+    /// instead of panicking if the precision is zero, where there are no digits to have a lowest one
+    pub fn checked_ulp(&self) -> Result<Self, Error> {
+        if self.precision == 0 {
+            return Err(Error::ZeroPrecision);
+        }
+        if self.mantissa == ibig!(0) {
+            return Ok(Self::from_integer(ibig!(0), self.precision));
+        }
+        let (_, exponent) = self.full_parts();
+        Ok(Self::from_parts_with_precision(ibig!(1), exponent, self.precision))
+    }
+
+    /// Get the smallest number larger than this number with the same precision
+    ///
+    /// # Panics
+    /// If the number is zero, since there's no smallest positive number,
+    /// or the precision is zero, see [Self::checked_next_up]
+    #[inline]
+    pub fn next_up(&self) -> Self {
+        self.checked_next_up().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the largest number smaller than this number with the same precision
+    ///
+    /// # Panics
+    /// If the number is zero, since there's no largest negative number,
+    /// or the precision is zero, see [Self::checked_next_down]
+    #[inline]
+    pub fn next_down(&self) -> Self {
+        self.checked_next_down().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the smallest number larger than this number with the same precision (see [Self::next_up]),
+    /// returning an error instead of panicking if the number is zero or the precision is zero
+    #[inline]
+    pub fn checked_next_up(&self) -> Result<Self, Error> {
+        self.next_toward(true)
+    }
+
+    /// Get the largest number smaller than this number with the same precision (see [Self::next_down]),
+    /// returning an error instead of panicking if the number is zero or the precision is zero
+    #[inline]
+    pub fn checked_next_down(&self) -> Result<Self, Error> {
+        self.next_toward(false)
+    }
+
+    /// Get the adjacent number toward the other number with the precision of this number,
+    /// the number is returned unchanged if the two numbers are equal.
+    ///
+    /// # Panics
+    /// If the numbers are not equal, and the number is zero or its precision is zero
+    pub fn next_after<const E2: usize, const R2: u8>(&self, other: &FloatRepr<E2, R2>) -> Self {
+        match self.partial_cmp(other) {
+            Some(Ordering::Less) => self.next_up(),
+            Some(Ordering::Greater) => self.next_down(),
+            _ => self.clone(),
+        }
+    }
+
+    /// Get the mantissa and exponent of the number such that the mantissa has exactly `precision` digits
+    fn full_parts(&self) -> (IBig, isize) {
+        let shift = self.precision.saturating_sub(self.actual_precision());
+        let mut mantissa = self.mantissa.clone();
+        shl_radix::<E>(&mut mantissa, shift);
+        (mantissa, self.exponent - shift as isize)
+    }
+
+    /// Move the number to the adjacent number, upward or downward
+    fn next_toward(&self, up: bool) -> Result<Self, Error> {
+        if self.precision == 0 {
+            return Err(Error::ZeroPrecision);
+        }
+        if self.mantissa == ibig!(0) {
+            // there's no adjacent number to zero
+            return Err(Error::Domain);
+        }
+        let (mut mantissa, mut exponent) = self.full_parts();
+
+        // moving toward zero from a power of the radix enters the range with a smaller ulp
+        let toward_zero = up == (mantissa < ibig!(0));
+        let mut lowest = ibig!(1);
+        shl_radix::<E>(&mut lowest, self.precision - 1);
+        if toward_zero && (mantissa == lowest || mantissa == -&lowest) {
+            shl_radix::<E>(&mut mantissa, 1);
+            exponent -= 1;
+        }
+
+        if up {
+            mantissa += 1u8;
+        } else {
+            mantissa -= 1u8;
+        }
+        Ok(Self::from_parts_with_precision(mantissa, exponent, self.precision))
+    }
+}
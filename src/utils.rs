@@ -1,7 +1,7 @@
 use core::cmp::Ordering;
 use core::convert::TryInto;
 
-use ibig::{IBig, UBig, ibig, ubig, ops::DivRem};
+use ibig::{IBig, ibig, ops::{Abs, DivRem}};
 use crate::{repr::RoundingMode, ibig_ext::{log, magnitude}};
 
 /// Get the integer k such that `radix^(k-1) <= value < radix^k`.
@@ -12,7 +12,6 @@ pub fn get_precision<const E: usize>(value: &IBig) -> usize{
     };
 
     let e = log(&magnitude(value), E);
-    let e: usize = e.try_into().unwrap();
     e + 1
 }
 
@@ -32,10 +31,19 @@ pub fn shl_radix<const E: usize>(value: &mut IBig, exp: usize) {
     }
 }
 
-/// "Right shifting" in given radix, i.e. divide by a power of radix
+/// "Right shifting" in given radix, i.e. divide by a power of radix.
+/// The result is truncated toward zero.
 #[inline]
 pub fn shr_radix<const E: usize>(value: &mut IBig, exp: usize) {
     if exp != 0 {
+        if *value < ibig!(0) {
+            // the bit shifts are rounding toward -inf, so always shift the magnitude
+            *value = -core::mem::take(value);
+            shr_radix::<E>(value, exp);
+            *value = -core::mem::take(value);
+            return;
+        }
+
         match E {
             2 => *value >>= exp,
             10 => {
@@ -48,109 +56,135 @@ pub fn shr_radix<const E: usize>(value: &mut IBig, exp: usize) {
     }
 }
 
-/// Calculate the high parts of a * b.
+/// "Right shifting" in given radix, i.e. divide by a power of radix.
+/// It returns the "shifted" value and the "remainder" part of integer that got removed.
 /// 
-/// It's equivalent to find `a * b / E^c` such that it's in the range `[E^(prec-1), E^prec)`
+/// The shifted value is truncated toward zero, so the remainder has the same sign as the value.
 #[inline]
-pub fn mul_hi<const E: usize>(a: &IBig, b: &IBig, prec: usize) -> IBig {
-    let mut c = a * b;
-    let prec_actual = get_precision::<E>(&c);
-    if prec_actual > prec {
-        shr_radix::<E>(&mut c, prec_actual - prec);
+pub fn shr_rem_radix<const E: usize>(value: &IBig, exp: usize) -> (IBig, IBig) {
+    if exp == 0 {
+        return (value.clone(), ibig!(0));
+    }
+    if *value < ibig!(0) {
+        let (q, r) = shr_rem_radix::<E>(&-value, exp);
+        return (-q, -r);
     }
-    c
-}
 
-// TODO: remove, rename or make a trait or upstream this? (check whether this is in GMP)
-/// "Right shifting" in given radix, i.e. divide by a power of radix
-#[inline]
-pub fn ushr_radix<const E: usize>(value: &mut UBig, exp: usize) {
-    if exp != 0 {
-        match E {
-            2 => *value >>= exp,
-            10 => {
-                *value >>= exp;
-                *value /= ubig!(5).pow(exp);
-            }
-            16 => *value >>= 4 * exp,
-            _ => *value /= UBig::from(E).pow(exp)
-        }
+    match E {
+        2 => {
+            // FIXME: a dedicate method to extract low bits for IBig might be helpful here
+            let rem = value & ((ibig!(1) << exp) - 1u8);
+            (value >> exp, rem)
+        },
+        10 => {
+            let rem1 = value & ((ibig!(1) << exp) - 1u8);
+            let (q, rem2) = (value >> exp).div_rem(ibig!(5).pow(exp));
+            let rem = (rem2 << exp) + rem1;
+            (q, rem)
+        },
+        16 => {
+            let rem = value & ((ibig!(1) << (4 * exp)) - 1u8);
+            (value >> (4 * exp), rem)
+        },
+        _ => value.div_rem(IBig::from(E).pow(exp))
     }
 }
 
-/// "Right shifting" in given radix, i.e. divide by a power of radix.
-/// It returns the "shifted" value and the "remainder" part of integer that got removed
+/// "Right shifting" in given radix, and round the result following the rounding mode.
 #[inline]
-pub fn shr_rem_radix<const E: usize>(value: &IBig, exp: usize) -> (IBig, IBig) {
-    
-    if exp != 0 {
-        match E {
-            2 => {
-                // FIXME: a dedicate method to extract low bits for IBig might be helpful here
-                let rem = value & ((ibig!(1) << exp) - 1u8);
-                (value >> exp, rem)
-            },
-            10 => {
-                let rem1 = value & ((ibig!(1) << exp) - 1u8);
-                let (q, rem2) = (value >> exp).div_rem(ibig!(5).pow(exp));
-                let rem = (rem2 << exp) + rem1;
-                (q, rem)
-            },
-            16 => {
-                let rem = value & ((ibig!(1) << (4 * exp)) - 1u8);
-                (value >> 4 * exp, rem)
-            },
-            _ => value.div_rem(IBig::from(E).pow(exp))
-        }
-    } else {
-        (value.clone(), ibig!(0))
+pub fn shr_round_radix<const E: usize>(value: &IBig, exp: usize, mode: u8) -> IBig {
+    let (mut q, r) = shr_rem_radix::<E>(value, exp);
+    if r != ibig!(0) {
+        let mut unit = ibig!(1);
+        shl_radix::<E>(&mut unit, exp);
+        round_with_fract(&mut q, &r, &unit, mode);
     }
+    q
 }
 
-/// Return the rounding bit based on the remainder (mod Radix)
+/// Round the mantissa based on the remainder `rem` of the division by `den`, where the
+/// mantissa is truncated toward zero and the remainder has the same sign as the value.
 #[inline]
-pub fn round_with_rem<const E: usize, const R: u8>(mantissa: &mut IBig, rem: isize) {
-    assert!((rem.abs() as usize) < E);
+pub fn round_with_fract(mantissa: &mut IBig, rem: &IBig, den: &IBig, mode: u8) {
+    let sign: isize = rem.signum().try_into().unwrap();
+    let half = (rem.abs() << 1).cmp(&den.abs());
+    round_with_half(mantissa, sign, half, mode);
+}
 
-    match (R, rem.signum()) {
-        (_, 0) => {},
-        (RoundingMode::Zero, _) => {},
-        (RoundingMode::Down, 1) => {},
-        (RoundingMode::Down, -1) => *mantissa -= 1u8,
-        (RoundingMode::Up, 1) => *mantissa += 1u8,
-        (RoundingMode::Up, -1) => {},
-        (RoundingMode::HalfEven | RoundingMode::HalfAway, _) => {
-            let double = if rem < 0 {
-                (rem + E as isize) * 2
-            } else {
-                rem * 2
-            } as usize;
-            match E.cmp(&double) {
-                Ordering::Greater => if rem > 0 {
-                    *mantissa += 1u8
-                },
-                Ordering::Equal => match R {
-                    RoundingMode::HalfEven => {
-                        // ties to even
-                        if &*mantissa % 2i8 != 0 {
-                            *mantissa += 1u8;
-                        }
-                    },
-                    RoundingMode::HalfAway => {
-                        // ties away from zero
-                        if rem > 0 {
-                            *mantissa += 1u8;
-                        } else {
-                            *mantissa -= 1u8;
-                        }
-                    },
+/// Round the truncated mantissa given the sign of the removed part, and the
+/// comparison between the (absolute value of) removed part and a half unit.
+/// 
+/// The rounding mode is one of [RoundingMode], it's passed as a value so that
+/// it can be selected at runtime.
+pub fn round_with_half(mantissa: &mut IBig, sign: isize, half: Ordering, mode: u8) {
+    if sign == 0 {
+        return;
+    }
+
+    match mode {
+        RoundingMode::Zero => {},
+        RoundingMode::Down => if sign < 0 {
+            *mantissa -= 1u8
+        },
+        RoundingMode::Up => if sign > 0 {
+            *mantissa += 1u8
+        },
+        RoundingMode::AwayFromZero => *mantissa += sign,
+        RoundingMode::ToOdd => if &*mantissa % 2i8 == 0 {
+            // the truncated value is even, so the odd neighbor is in the direction of the value
+            *mantissa += sign;
+        },
+        RoundingMode::HalfEven | RoundingMode::HalfAway | RoundingMode::HalfZero
+            | RoundingMode::HalfUp | RoundingMode::HalfOdd => {
+            let away = match half {
+                Ordering::Greater => true,
+                Ordering::Equal => match mode {
+                    // ties to even
+                    RoundingMode::HalfEven => &*mantissa % 2i8 != 0,
+                    // ties away from zero
+                    RoundingMode::HalfAway => true,
+                    // ties toward zero
+                    RoundingMode::HalfZero => false,
+                    // ties toward +infinity
+                    RoundingMode::HalfUp => sign > 0,
+                    // ties to odd
+                    RoundingMode::HalfOdd => &*mantissa % 2i8 == 0,
                     _ => unreachable!()
                 },
-                Ordering::Less => if rem < 0 {
-                    *mantissa -= 1u8
-                }
+                Ordering::Less => false
             };
+            if away {
+                *mantissa += sign;
+            }
         },
         _ => unreachable!()
     }
 }
+
+/// Get the working precisions for the iterations of a quadratically convergent method,
+/// in increasing order, starting from an estimate with `init` correct digits.
+/// 
+/// The final precision is repeated so that the last iteration can confirm the result.
+pub fn newton_schedule(init: usize, precision: usize) -> Vec<usize> {
+    let mut schedule = vec![precision, precision];
+    let mut current = precision;
+    while current > init.max(1) {
+        current = current.div_ceil(2);
+        schedule.push(current);
+    }
+    schedule.reverse();
+    schedule
+}
+
+/// Decompose the radix as `base^exp` with the smallest possible base
+pub fn perfect_power(radix: usize) -> (usize, u32) {
+    for exp in (2..usize::BITS).rev() {
+        let root = (radix as f64).powf(1. / exp as f64).round() as usize;
+        for base in root.saturating_sub(1).max(2)..=root + 1 {
+            if base.checked_pow(exp) == Some(radix) {
+                return (base, exp);
+            }
+        }
+    }
+    (radix, 1)
+}
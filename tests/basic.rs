@@ -1,11 +1,15 @@
 use std::str::FromStr;
 
 use ibig::ibig;
-use fbig::{FBig, DBig};
+use fbig::{FBig, DBig, Approximation, Error, RoundingDirection};
+
+fn dbig(s: &str) -> DBig {
+    DBig::from_str(s).unwrap()
+}
 
 #[test]
 fn test_print() {
-    let f = FBig::from(-1.2f32);
+    let _f = FBig::from(-1.2f32);
     // dbg!(&f);
     // println!("{}", f);
     // for i in 0..10 {
@@ -35,3 +39,305 @@ fn test_print() {
     let f = DBig::from_str("121241431345234523452.234523452345234523534e-12").unwrap();
     println!("{}", f);
 }
+
+#[test]
+fn test_cmp() {
+    let d = |s: &str, p: usize| DBig::from_str(s).unwrap().with_precision(p);
+
+    // precision is ignored
+    assert_eq!(d("1.5", 2), d("1.5", 100));
+    assert_eq!(d("0", 1), d("0", 10));
+    assert_ne!(d("1.5", 10), d("1.51", 10));
+
+    assert!(d("1.5", 10) < d("1.51", 3));
+    assert!(d("-1.5", 10) > d("-1.51", 10));
+    assert!(d("-1e10", 10) < d("1e-10", 10));
+    assert!(d("0", 10) < d("1e-10", 10));
+    assert!(d("-1e-10", 10) < d("0", 10));
+    assert!(d("99", 10) < d("100", 10));
+    assert!(d("-99", 10) > d("-100", 10));
+    assert!(d("123.4", 10) > d("123.39999", 10));
+
+    assert_eq!(FBig::from(0.5f64).max(FBig::from(0.25f64)), FBig::from(0.5f64));
+    let mut values = vec![d("3", 5), d("-2", 5), d("0.5", 5), d("-2.5", 5)];
+    values.sort();
+    assert_eq!(values, vec![d("-2.5", 5), d("-2", 5), d("0.5", 5), d("3", 5)]);
+}
+
+#[test]
+fn test_hash() {
+    use std::collections::HashSet;
+    let d = |s: &str, p: usize| DBig::from_str(s).unwrap().with_precision(p);
+
+    let mut set = HashSet::new();
+    set.insert(d("1.0", 10));
+    set.insert(d("1", 100));
+    set.insert(d("1.00", 2));
+    set.insert(d("0", 5));
+    set.insert(d("0.0", 50));
+    set.insert(d("10", 5));
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(&d("1", 1)));
+}
+
+#[test]
+fn test_cmp_cross_radix() {
+    let d = |s: &str| DBig::from_str(s).unwrap();
+
+    assert_eq!(FBig::from(0.5f64), d("0.5"));
+    let tenth = FBig::from(0.1f64);
+    assert_ne!(tenth, d("0.1"));
+    assert!(tenth > d("0.1"));
+    assert_eq!(tenth, d("0.1000000000000000055511151231257827021181583404541015625"));
+    assert!(tenth < d("0.1000000000000000055511151231257827021181583404541015626"));
+    assert!(d("-0.1000000000000000055511151231257827021181583404541015626") < -tenth);
+    assert!(FBig::from_parts(ibig!(1), -1328) > d("1e-400"));
+    assert!(FBig::from_parts(ibig!(1), 1329) < d("1e401"));
+
+    // near-equal values with huge exponents are separated without computing the full powers
+    let huge = d("1e1000000");
+    let bin = huge.clone().with_radix_and_precision_approx::<2>(64);
+    assert!(matches!(bin, Approximation::Inexact(_, RoundingDirection::Down)));
+    assert!(bin.value_ref() < &huge);
+    assert!(huge > *bin.value_ref());
+    assert_eq!(FBig::from_parts(ibig!(3), -30), d("0.000000002793967723846435546875"));
+
+    // primitive numbers
+    assert_eq!(d("0.5"), 0.5f64);
+    assert_ne!(d("0.1"), 0.1f64);
+    assert!(d("0.1") < 0.1f64);
+    assert!(0.1f64 > d("0.1"));
+    assert!(d("1e400") < f64::INFINITY);
+    assert!(d("-1e400") > f64::NEG_INFINITY);
+    assert_eq!(d("1").partial_cmp(&f64::NAN), None);
+    assert_eq!(d("300"), 300i64);
+    assert!(d("2.5") < 3i64);
+    assert!(-3i64 < d("-2.5"));
+    assert_eq!(d("1e30"), ibig!(1000000000000000000000000000000));
+}
+
+#[test]
+fn test_sign_utilities() {
+    use std::cmp::Ordering;
+    let d = |s: &str| DBig::from_str(s).unwrap();
+
+    assert_eq!(d("-1.5").abs(), d("1.5"));
+    assert_eq!(d("-1.5").signum(), d("-1"));
+    assert_eq!(d("0").signum(), d("0"));
+    assert_eq!(d("2e10").signum(), d("1"));
+    assert!(d("0").is_sign_positive() && !d("0").is_sign_negative());
+    assert!(d("-1e-10").is_sign_negative());
+    assert_eq!(d("1.5").copysign(&d("-2")), d("-1.5"));
+    assert_eq!(d("-1.5").copysign(&d("0")), d("1.5"));
+    assert!(d("0").is_zero() && !d("1e-100").is_zero());
+    assert!(d("0").is_integer() && d("1e3").is_integer() && d("-12").is_integer());
+    assert!(!d("1.5").is_integer() && !d("1e-3").is_integer());
+
+    assert_eq!(d("1").max(d("2")), d("2"));
+    assert_eq!(d("1").min(d("-2")), d("-2"));
+    assert_eq!(d("5").clamp(d("0"), d("1.5")), d("1.5"));
+    assert_eq!(d("1.0").total_cmp(&d("1.00").with_precision(3)), Ordering::Less);
+    assert_eq!(d("-1").total_cmp(&d("1")), Ordering::Less);
+}
+
+#[test]
+fn test_digit_counts() {
+    let d = |s: &str| DBig::from_str(s).unwrap();
+
+    assert_eq!(d("1200").trailing_zeros(), Some(2));
+    assert_eq!(d("1.25").trailing_zeros(), Some(0));
+    assert_eq!(d("0").trailing_zeros(), None);
+    assert_eq!(FBig::from(24.).trailing_zeros(), Some(3));
+
+    assert_eq!(d("1200").int_digits(), 4);
+    assert_eq!(d("-12.345").int_digits(), 2);
+    assert_eq!(d("0.05").int_digits(), 0);
+    assert_eq!(d("0").int_digits(), 0);
+    assert_eq!(d("-12.345").frac_digits(), 3);
+    assert_eq!(d("0.05").frac_digits(), 2);
+    assert_eq!(d("1e3").frac_digits(), 0);
+    assert_eq!(FBig::from(0.375).frac_digits(), 3);
+}
+
+#[test]
+fn test_ulp() {
+    let d = |s: &str| DBig::from_str(s).unwrap().with_precision(3);
+
+    assert_eq!(d("1").ulp(), d("0.01"));
+    assert_eq!(d("-123").ulp(), d("1"));
+    assert_eq!(d("0").ulp(), d("0"));
+    assert_eq!(DBig::epsilon(3), d("0.01"));
+    assert_eq!(d("1").next_up(), d("1.01"));
+    assert_eq!(d("1").next_down(), d("0.999"));
+    assert_eq!(d("-1").next_up(), d("-0.999"));
+    assert_eq!(d("-1").next_down(), d("-1.01"));
+    assert_eq!(d("9.99").next_up(), d("10"));
+    assert_eq!(d("10").next_up(), d("10.1"));
+    assert_eq!(d("1.5e-10").next_down(), d("1.49e-10"));
+    assert_eq!(d("1").next_up().precision(), 3);
+
+    assert_eq!(d("1").next_after(&d("2")), d("1.01"));
+    assert_eq!(d("1").next_after(&d("1")), d("1"));
+    assert_eq!(d("1").next_after(&d("0")), d("0.999"));
+    assert_eq!(FBig::from(1.).next_after(&d("0.5")), FBig::from(1. - f64::EPSILON / 2.));
+
+    // the adjacent numbers of f64
+    let one = FBig::from(1.);
+    assert_eq!(one.ulp(), FBig::from(f64::EPSILON));
+    assert_eq!(FBig::epsilon(53), FBig::from(f64::EPSILON));
+    assert_eq!(one.next_up(), FBig::from(1. + f64::EPSILON));
+    for x in [0.1f64, -0.1, 1e300, -2.5e-300, 0.5] {
+        let up = f64::from_bits(if x > 0. { x.to_bits() + 1 } else { x.to_bits() - 1 });
+        let down = f64::from_bits(if x > 0. { x.to_bits() - 1 } else { x.to_bits() + 1 });
+        assert_eq!(FBig::from(x).next_up(), FBig::from(up));
+        assert_eq!(FBig::from(x).next_down(), FBig::from(down));
+    }
+}
+
+#[test]
+fn test_ulp_errors() {
+    let x = dbig("7").with_precision(0);
+    assert_eq!(x.checked_ulp(), Err(Error::ZeroPrecision));
+    assert_eq!(x.checked_next_up(), Err(Error::ZeroPrecision));
+    assert_eq!(x.checked_next_down(), Err(Error::ZeroPrecision));
+    assert_eq!(DBig::checked_epsilon(0), Err(Error::ZeroPrecision));
+    assert_eq!(dbig("0").with_precision(3).checked_next_up(), Err(Error::Domain));
+    assert_eq!(dbig("1").with_precision(3).checked_next_up(), Ok(dbig("1.01").with_precision(3)));
+}
+
+#[test]
+#[should_panic(expected = "precision is zero")]
+fn test_next_up_zero_precision() {
+    let _ = dbig("7").with_precision(0).next_up();
+}
+
+#[test]
+fn test_frexp() {
+    let d = |s: &str| DBig::from_str(s).unwrap();
+
+    assert_eq!(d("1.5").scale_radix(3), d("1500"));
+    assert_eq!(d("-1.5").scale_radix(-3), d("-0.0015"));
+    assert_eq!(d("0").scale_radix(100), d("0"));
+    assert_eq!(d("1.5").scale_radix(3).precision(), 2);
+
+    assert_eq!(d("123.45").frexp(), (d("0.12345"), 3));
+    assert_eq!(d("-0.00123").frexp(), (d("-0.123"), -2));
+    assert_eq!(d("1").frexp(), (d("0.1"), 1));
+    assert_eq!(d("0").frexp(), (d("0"), 0));
+    assert_eq!(DBig::ldexp(d("0.12345"), 3), d("123.45"));
+    assert_eq!(FBig::from(12.).frexp(), (FBig::from(0.75), 4));
+    assert_eq!(FBig::ldexp(FBig::from(0.75), -4), FBig::from(0.046875));
+
+    assert_eq!(d("123.45").ilogb(), Some(2));
+    assert_eq!(d("0.001").ilogb(), Some(-3));
+    assert_eq!(d("0").ilogb(), None);
+    assert_eq!(d("-999").log_radix_floor(), 2);
+    assert_eq!(FBig::from(0.1).log_radix_floor(), -4);
+}
+
+#[test]
+fn test_precision_units() {
+    let d = |s: &str| DBig::from_str(s).unwrap();
+
+    assert_eq!(d("1").with_bits(53).precision(), 16);
+    assert_eq!(d("1").with_bits(53).precision_bits(), 53);
+    assert_eq!(d("1").with_decimal_digits(5).precision(), 5);
+    assert_eq!(d("1").with_precision(16).precision_decimal_digits(), 16);
+    assert_eq!(d("1").with_precision(16).precision_bits(), 53);
+    assert_eq!(d("1").with_bits(0).precision(), 0);
+
+    let f = FBig::from(0.1);
+    assert_eq!(f.precision_bits(), 53);
+    assert_eq!(f.precision_decimal_digits(), 15);
+    assert_eq!(f.clone().with_bits(24).precision(), 24);
+    assert_eq!(f.clone().with_decimal_digits(16).precision(), 54);
+    assert_eq!(f.clone().with_decimal_digits(3), FBig::from(0.0999755859375));
+    assert_eq!(f.with_bits(24), FBig::from(0.1f32));
+
+    let h = fbig::FloatRepr::<16, 0>::from_integer(ibig!(1), 3);
+    assert_eq!(h.precision_bits(), 12);
+    assert_eq!(h.clone().with_bits(12).precision(), 3);
+    assert_eq!(h.with_bits(13).precision(), 4);
+}
+
+#[test]
+fn test_radix_conversion() {
+    use fbig::{FloatRepr, RoundingMode};
+    type Hex = FloatRepr<16, {RoundingMode::HalfEven}>;
+    type Oct = FloatRepr<8, {RoundingMode::HalfEven}>;
+    type Base32 = FloatRepr<32, {RoundingMode::HalfEven}>;
+
+    // radices that are powers of a common base are converted exactly
+    let x = FBig::from(0.1);
+    let hex = x.clone().with_radix_and_precision::<16>(14);
+    assert_eq!(hex, Hex::from_parts(ibig!(0x1999999999999a), -14));
+    assert_eq!(hex.clone().with_radix::<2>(), x);
+    assert_eq!(x.clone().with_radix_and_precision::<16>(3), Hex::from_parts(ibig!(0x19a), -3));
+    let down = x.clone().with_rounding::<{RoundingMode::Zero}>().with_radix_and_precision::<16>(3);
+    assert_eq!(down, Hex::from_parts(ibig!(0x199), -3));
+    assert!(!x.clone().with_radix_and_precision_approx::<16>(3).is_exact());
+
+    let oct = Oct::from_str("0.1").unwrap();
+    assert_eq!(oct.with_radix_and_precision::<32>(1), Base32::from_str("0.4").unwrap());
+    assert_eq!(Oct::from_str("7.7").unwrap().with_radix_and_precision::<32>(2), Base32::from_str("7.s").unwrap());
+    assert_eq!(Base32::from_str("v.v").unwrap().with_radix_and_precision::<8>(4), Oct::from_str("37.76").unwrap());
+    assert_eq!(FBig::from(-1536.).with_radix_and_precision::<16>(1), Hex::from_parts(ibig!(-6), 2));
+
+    // other radices are correctly rounded
+    let d = DBig::from_str("0.1").unwrap();
+    assert_eq!(d.clone().with_radix_and_precision::<2>(53), x);
+    assert_eq!(d.clone().with_radix_and_precision::<2>(24), FBig::from(0.1f32));
+    assert_eq!(x.with_radix_and_precision::<10>(5), DBig::from_str("0.1").unwrap());
+    assert_eq!(DBig::from_str("1.5e300").unwrap().with_radix_and_precision::<16>(10).with_radix_and_precision::<10>(10),
+        DBig::from_str("1.5e300").unwrap());
+
+    // the round trip of a long number produces a long run of trailing zeros
+    let long = DBig::from_parts(ibig!(7).pow(2000), -2000);
+    let bin = long.clone().with_radix_and_precision::<2>(long.precision() * 4);
+    let back = bin.with_radix_and_precision::<10>(long.precision() + 100);
+    assert_eq!(back, long);
+    assert_eq!(back.actual_precision(), long.precision());
+
+    // the cost depends on the precision instead of the exponent
+    let tiny = dbig("1.5e-2000000").with_radix_and_precision_approx::<2>(24);
+    assert_eq!(tiny, Approximation::Inexact(FBig::from_parts(ibig!(11031975), -6643879), RoundingDirection::Down));
+    let huge = dbig("-7.25e1000000").with_radix_and_precision::<2>(24);
+    assert_eq!(huge, FBig::from_parts(ibig!(-16237975), 3321907));
+    let tiny = FBig::from_parts(ibig!(3), -3000000).with_radix_and_precision::<10>(20);
+    assert_eq!(tiny, DBig::from_parts(ibig!(30912157046359775604), -903109));
+
+    // at least one digit is kept in the new radix, which is also used by the alternate display
+    assert_eq!(FBig::from_parts(ibig!(3), -10).with_radix::<10>(), dbig("0.003"));
+    assert_eq!(format!("{:#}", FBig::from_parts(ibig!(3), -10)), "0.003");
+    let printed = format!("{:#}", FBig::from_parts(ibig!(3), -3000000));
+    assert!(printed.starts_with("0.000") && printed.ends_with("0003"));
+    assert_eq!(printed.len(), 903092);
+}
+
+#[test]
+fn test_radix_conversion_large() {
+    // long mantissas are printed and converted by divide-and-conquer over the powers of the radix
+    let mantissa = ibig!(3).pow(40000);
+    let digits = mantissa.to_string();
+    let (int, frac) = digits.split_at(digits.len() - 10000);
+    let x = DBig::from_parts(mantissa.clone(), -10000);
+    assert_eq!(x.to_string(), format!("{}.{}", int, frac));
+    let sparse = ibig!(10).pow(15000) + 1u8;
+    assert_eq!(DBig::from_parts(sparse.clone(), 0).to_string(), sparse.to_string());
+    assert_eq!(DBig::from_parts(-&sparse, 3).to_string(), format!("-{}000", sparse));
+
+    // the conversion of a binary number with a long mantissa is exact with enough digits
+    let bin = FBig::from_parts(mantissa.clone(), -20000);
+    let dec = bin.clone().with_radix_and_precision_approx::<10>(40000);
+    let digits = (&mantissa * ibig!(5).pow(20000)).to_string();
+    let (int, frac) = digits.split_at(digits.len() - 20000);
+    assert!(dec.is_exact());
+    assert_eq!(dec.value_ref().to_string(), format!("{}.{}", int, frac));
+    assert_eq!(dec.value().with_radix_and_precision::<2>(bin.precision()), bin);
+
+    // and it's correctly rounded to a short precision
+    let expected = FBig::from_ratio(mantissa.clone(), ibig!(10).pow(10000), 100);
+    assert_eq!(x.clone().with_radix_and_precision::<2>(100), expected);
+    let expected = DBig::from_ratio(mantissa, ibig!(2).pow(20000), 50);
+    assert_eq!(bin.with_radix_and_precision::<10>(50), expected);
+}
@@ -0,0 +1,228 @@
+use std::str::FromStr;
+
+use ibig::ibig;
+use fbig::{DBig, FBig, Context, Rounding, Signal, Signals, Approximation, RoundingDirection, Error};
+
+fn dbig(s: &str) -> DBig {
+    DBig::from_str(s).unwrap()
+}
+
+#[test]
+fn test_context_arithmetic() {
+    let ctx = Context::new(5);
+
+    // the precisions of the operands are ignored
+    let a = dbig("1.23456789");
+    let b = dbig("1e-10").with_precision(1);
+    assert_eq!(ctx.add(&a, &b).unwrap(), dbig("1.2346"));
+    assert_eq!(ctx.add(&a, &b).unwrap().precision(), 5);
+    assert_eq!(ctx.sub(&a, &dbig("1.23456")).unwrap(), dbig("7.89e-6"));
+    assert_eq!(ctx.mul(&a, &dbig("2")).unwrap(), dbig("2.4691"));
+    assert_eq!(ctx.div(&dbig("1"), &dbig("3")).unwrap(), dbig("0.33333"));
+    assert_eq!(ctx.sqrt(&dbig("2")).unwrap(), dbig("1.4142"));
+    assert_eq!(ctx.round(&a).unwrap(), dbig("1.2346"));
+    assert!(ctx.flags().inexact);
+
+    // a small operand that overlaps the extra digits of the other one can carry into the rounding
+    let c = dbig("1.004999999");
+    assert_eq!(Context::new(3).add(&c, &dbig("1.00000001e-8")).unwrap(), dbig("1.01"));
+
+    let mut ctx = Context::new(3);
+    ctx.rounding = Rounding::Up;
+    assert_eq!(ctx.div(&dbig("1"), &dbig("3")).unwrap(), dbig("0.334"));
+    assert_eq!(ctx.sqrt(&dbig("2")).unwrap(), dbig("1.42"));
+}
+
+#[test]
+fn test_context_signals() {
+    let mut ctx = Context::new(3);
+    assert_eq!(ctx.add(&dbig("1"), &dbig("2")).unwrap(), dbig("3"));
+    assert_eq!(ctx.flags(), Signals::default());
+
+    assert_eq!(ctx.div(&dbig("1"), &dbig("0")), Err(Signal::DivisionByZero));
+    assert_eq!(ctx.div(&dbig("0"), &dbig("0")), Err(Signal::InvalidOperation));
+    assert_eq!(ctx.sqrt(&dbig("-1")), Err(Signal::InvalidOperation));
+    assert!(ctx.flags().division_by_zero && ctx.flags().invalid_operation);
+    ctx.clear_flags();
+
+    // inexact results are only errors if trapped
+    ctx.traps.inexact = true;
+    assert_eq!(ctx.div(&dbig("1"), &dbig("3")), Err(Signal::Inexact));
+    assert_eq!(ctx.div(&dbig("1"), &dbig("4")).unwrap(), dbig("0.25"));
+    assert!(ctx.flags().inexact);
+
+    // exponent range
+    let mut ctx = Context::new(3);
+    ctx.emin = -10;
+    ctx.emax = 10;
+    assert_eq!(ctx.mul(&dbig("1e6"), &dbig("1e6")), Err(Signal::Overflow));
+    assert_eq!(ctx.mul(&dbig("9.999e10"), &dbig("1")), Err(Signal::Overflow));
+    assert_eq!(ctx.mul(&dbig("9.99e10"), &dbig("1")).unwrap(), dbig("9.99e10"));
+
+    ctx.clear_flags();
+    ctx.traps.overflow = false;
+    assert_eq!(ctx.mul(&dbig("-1e6"), &dbig("1e6")).unwrap(), dbig("-9.99e10"));
+    assert!(ctx.flags().overflow && ctx.flags().inexact);
+
+    // subnormal results are rounded at 1e-12
+    ctx.clear_flags();
+    assert_eq!(ctx.mul(&dbig("1.2e-5"), &dbig("1e-6")).unwrap(), dbig("1.2e-11"));
+    assert_eq!(ctx.div(&dbig("1"), &dbig("1e12")).unwrap(), dbig("1e-12"));
+    assert_eq!(ctx.flags(), Signals::default());
+    assert_eq!(ctx.mul(&dbig("1.234e-5"), &dbig("1e-6")).unwrap(), dbig("1.2e-11"));
+    assert_eq!(ctx.mul(&dbig("1.234e-6"), &dbig("1e-6")).unwrap(), dbig("1e-12"));
+    assert_eq!(ctx.mul(&dbig("6e-7"), &dbig("1e-6")).unwrap(), dbig("1e-12"));
+    assert_eq!(ctx.mul(&dbig("4e-7"), &dbig("1e-6")).unwrap(), dbig("0"));
+    assert_eq!(ctx.round(&dbig("-1e-100")).unwrap(), dbig("0"));
+    assert!(ctx.flags().underflow && ctx.flags().inexact);
+    ctx.traps.underflow = true;
+    assert_eq!(ctx.mul(&dbig("1e-7"), &dbig("1e-7")), Err(Signal::Underflow));
+    assert_eq!(ctx.mul(&dbig("1e-6"), &dbig("1e-6")).unwrap(), dbig("1e-12"));
+}
+
+#[test]
+fn test_ieee_context() {
+    // the binary64 context emulates f64
+    let ctx = Context::binary64();
+    let cases = [
+        (1., 3.), (2., 7.), (1e300, 1e-5), (f64::MIN_POSITIVE, 0.3), (f64::MIN_POSITIVE, 1e-10),
+        (5e-324, 0.5), (5e-324, 0.75), (-1e-200, 3e-120), (1.5e308, 1.1), (f64::MAX, 1.),
+    ];
+    for (a, b) in cases {
+        let (x, y) = (FBig::from(a), FBig::from(b));
+        assert_eq!(ctx.mul(&x, &y).unwrap(), FBig::from(a * b), "{} * {}", a, b);
+        assert_eq!(ctx.div(&x, &y).unwrap(), FBig::from(a / b), "{} / {}", a, b);
+        assert_eq!(ctx.add(&x, &y).unwrap(), FBig::from(a + b), "{} + {}", a, b);
+        assert_eq!(ctx.sqrt(&y).unwrap(), FBig::from(b.sqrt()), "sqrt {}", b);
+    }
+    assert_eq!(ctx.mul(&FBig::from(f64::MAX), &FBig::from(2.)), Err(Signal::Overflow));
+
+    let ctx = Context::decimal128();
+    assert_eq!(ctx.precision, 34);
+    assert_eq!(ctx.div(&dbig("1e-6143"), &dbig("3")).unwrap(), dbig("3.33333333333333333333333333333333e-6144"));
+    assert_eq!(ctx.div(&dbig("1e-6170"), &dbig("3")).unwrap(), dbig("3.33333e-6171"));
+    assert_eq!(ctx.mul(&dbig("1e6144"), &dbig("10")), Err(Signal::Overflow));
+}
+
+#[test]
+fn test_context_functions() {
+    let ctx = Context::new(10);
+    assert_eq!(ctx.exp(&dbig("1")).unwrap(), dbig("2.718281828"));
+    assert_eq!(ctx.exp(&dbig("-1")).unwrap(), dbig("0.3678794412"));
+    assert_eq!(ctx.ln(&dbig("2")).unwrap(), dbig("0.6931471806"));
+    assert_eq!(ctx.ln(&dbig("10")).unwrap(), dbig("2.302585093"));
+    assert_eq!(ctx.ln(&dbig("10")).unwrap().precision(), 10);
+    ctx.clear_flags();
+    assert_eq!(ctx.exp(&dbig("0")).unwrap(), dbig("1"));
+    assert_eq!(ctx.ln(&dbig("1")).unwrap(), dbig("0"));
+    assert_eq!(ctx.flags(), Signals::default());
+    assert_eq!(ctx.ln(&dbig("0")), Err(Signal::DivisionByZero));
+    assert_eq!(ctx.ln(&dbig("-1")), Err(Signal::InvalidOperation));
+
+    let mut ctx = Context::new(10);
+    ctx.rounding = Rounding::Up;
+    assert_eq!(ctx.exp(&dbig("1")).unwrap(), dbig("2.718281829"));
+    assert_eq!(ctx.exp(&dbig("0.5")).unwrap(), dbig("1.648721271"));
+
+    // the exponent range is enforced on the functions
+    let mut ctx = Context::new(3);
+    ctx.emin = -10;
+    ctx.emax = 10;
+    assert_eq!(ctx.exp(&dbig("30")), Err(Signal::Overflow));
+    assert_eq!(ctx.exp(&dbig("1e10")), Err(Signal::Overflow));
+    ctx.traps.overflow = false;
+    assert_eq!(ctx.exp(&dbig("1e10")).unwrap(), dbig("9.99e10"));
+    ctx.clear_flags();
+    assert_eq!(ctx.exp(&dbig("-25")).unwrap(), dbig("1.4e-11"));
+    assert_eq!(ctx.exp(&dbig("-30")).unwrap(), dbig("0"));
+    assert_eq!(ctx.exp(&dbig("-1e10")).unwrap(), dbig("0"));
+    assert_eq!(ctx.ln(&dbig("1.00000000001")).unwrap(), dbig("1e-11"));
+    assert!(ctx.flags().underflow && !ctx.flags().overflow);
+
+    // quantize and convert
+    assert_eq!(ctx.quantize(&dbig("1.2345"), 2).unwrap(), dbig("1.23"));
+    assert_eq!(ctx.quantize(&dbig("12.345"), 2), Err(Signal::InvalidOperation));
+    assert_eq!(ctx.quantize(&dbig("1e-11"), 13), Err(Signal::InvalidOperation));
+    assert_eq!(ctx.quantize(&dbig("1e5"), -11), Err(Signal::InvalidOperation));
+
+    let ctx = Context::binary64();
+    let to_binary = |s: &str| ctx.convert::<10, 2, _>(&dbig(s));
+    assert_eq!(to_binary("0.1").unwrap(), FBig::from(0.1));
+    assert_eq!(to_binary("1e-320").unwrap(), FBig::from(1e-320));
+    assert_eq!(to_binary("-2e-324").unwrap(), FBig::from(0.));
+    assert_eq!(to_binary("2e308"), Err(Signal::Overflow));
+    let decimal: DBig = Context::new(5).convert(&FBig::from(0.1)).unwrap();
+    assert_eq!(decimal, dbig("0.1"));
+}
+
+#[test]
+#[should_panic(expected = "exponent overflow")]
+fn test_exponent_overflow() {
+    let x = DBig::from_parts(ibig!(1), isize::MAX / 2 + 1);
+    let _ = &x * &x;
+}
+
+#[test]
+fn test_thread_context() {
+    assert_eq!(Context::with_current(|ctx| ctx.precision), 28);
+    Context::set_current(Context::new(4));
+    let third = Context::with_current(|ctx| ctx.div(&dbig("1"), &dbig("3"))).unwrap();
+    assert_eq!(third, dbig("0.3333"));
+    assert!(Context::with_current(|ctx| ctx.flags().inexact));
+
+    // other threads keep the default context
+    let precision = std::thread::spawn(|| Context::with_current(|ctx| ctx.precision)).join().unwrap();
+    assert_eq!(precision, 28);
+}
+
+#[test]
+fn test_thread_context_range() {
+    // before a context is installed, the operations are only bounded by isize
+    let huge = dbig("1e600000");
+    assert_eq!(&huge * &huge, dbig("1e1200000"));
+
+    // the plain operations follow the exponent range of the installed context, with their own precisions
+    let mut ctx = Context::binary64();
+    ctx.traps.overflow = false;
+    Context::set_current(ctx);
+    let cases = [(1e-300, 1e-10), (f64::MIN_POSITIVE, 0.3), (5e-324, 0.5), (-1e-200, 3e-120), (1.5e308, 1.1)];
+    for (a, b) in cases {
+        let (x, y) = (FBig::from(a), FBig::from(b));
+        assert_eq!(&x * &y, FBig::from(a * b), "{} * {}", a, b);
+        assert_eq!(&x / &y.recip(), FBig::from(a / (1. / b)), "{} / (1 / {})", a, b);
+    }
+    assert_eq!(FBig::from(1e-320).sqrt(), FBig::from(1e-320f64.sqrt()));
+    assert!(Context::with_current(|ctx| ctx.flags().underflow && !ctx.flags().overflow));
+
+    // overflows saturate to the largest finite number, which is below the exact result
+    let max = FBig::from(f64::MAX);
+    assert_eq!((&max * &FBig::from(2.)).to_f64(), f64::MAX);
+    assert!(matches!(max.mul_approx(&FBig::from(-2.)), Approximation::Inexact(v, RoundingDirection::Up) if v == -&max));
+    assert_eq!(max.checked_add(&max), Ok(max.clone()));
+    assert!(Context::with_current(|ctx| ctx.flags().overflow));
+
+    // the intermediate results of the functions are not limited
+    let erfc = dbig("3").with_precision(10).erfc();
+    let mut ctx = Context::new(10);
+    ctx.emin = -6;
+    ctx.emax = 6;
+    Context::set_current(ctx);
+    assert_eq!(dbig("3").with_precision(10).erfc(), erfc);
+    assert_eq!(dbig("1e-5").with_precision(10).exp(), dbig("1.000010000"));
+    assert_eq!(dbig("-20").with_precision(10).exp(), dbig("2.061154e-9"));
+    assert!(Context::with_current(|ctx| ctx.flags().underflow && !ctx.flags().overflow));
+
+    // trapped signals are errors of the checked operations
+    assert_eq!(dbig("1e4").checked_mul(&dbig("1e4")), Err(Error::ExponentOverflow));
+    assert_eq!(dbig("1e-4").checked_div(&dbig("1e4")).unwrap(), dbig("0"));
+    assert_eq!(dbig("1e-4").with_precision(10).checked_div(&dbig("1e4")).unwrap(), dbig("1e-8"));
+    Context::reset_current();
+    assert_eq!(dbig("1e4").checked_mul(&dbig("1e4")).unwrap(), dbig("1e8"));
+}
+
+#[test]
+#[should_panic(expected = "exponent overflow")]
+fn test_thread_context_overflow() {
+    Context::set_current(Context::binary32());
+    let _ = FBig::from(1e30) * FBig::from(1e30);
+}
@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use ibig::{ibig, error::ParseError};
+use fbig::{FBig, DBig, Error, FloatRepr, PrecisionPolicy, RoundingMode};
+
+fn dbig(s: &str, precision: usize) -> DBig {
+    DBig::from_str(s).unwrap().with_precision(precision)
+}
+
+#[test]
+fn test_arithmetic() {
+    assert_eq!(dbig("1.5", 10) + dbig("1", 10), dbig("2.5", 10));
+    assert_eq!(dbig("1", 10) - dbig("1e-20", 10), dbig("1", 10));
+    assert_eq!(dbig("2", 20) / dbig("3", 20), dbig("0.66666666666666666667", 20));
+    assert_eq!(dbig("3", 20).recip(), dbig("0.33333333333333333333", 20));
+    assert_eq!(dbig("3", 20).sqrt(), dbig("1.7320508075688772935", 20));
+    assert_eq!(FBig::from(2.25f64).sqrt(), FBig::from(1.5f64).with_precision(53));
+
+    // a half unit is not a digit in odd radices
+    type Ternary = FloatRepr<3, {RoundingMode::HalfEven}>;
+    assert_eq!(Ternary::from_ratio(ibig!(5), ibig!(7), 5), Ternary::from_parts(ibig!(58), -4));
+    assert_eq!(Ternary::from_parts(ibig!(28), 0).sqrt(), Ternary::from_parts(ibig!(16), -1));
+}
+
+#[test]
+fn test_checked_arithmetic() {
+    assert_eq!(dbig("1.5", 10).checked_add(&dbig("1", 10)), Ok(dbig("2.5", 10)));
+    assert_eq!(dbig("1.5", 10).checked_sub(&dbig("1", 10)), Ok(dbig("0.5", 10)));
+    assert_eq!(dbig("1.5", 10).checked_mul(&dbig("2", 10)), Ok(dbig("3", 10)));
+    assert_eq!(dbig("2", 20).checked_div(&dbig("3", 20)), Ok(dbig("0.66666666666666666667", 20)));
+    assert_eq!(dbig("3", 20).checked_recip(), Ok(dbig("0.33333333333333333333", 20)));
+    assert_eq!(dbig("3", 20).checked_sqrt(), Ok(dbig("1.7320508075688772935", 20)));
+
+    assert_eq!(dbig("1", 10).checked_div(&dbig("0", 10)), Err(Error::DivisionByZero));
+    assert_eq!(dbig("0", 10).checked_recip(), Err(Error::DivisionByZero));
+    assert_eq!(dbig("-2", 10).checked_sqrt(), Err(Error::Domain));
+
+    let huge = DBig::from_parts(ibig!(1), isize::MAX - 10);
+    let tiny = DBig::from_parts(ibig!(1), isize::MIN + 10);
+    assert_eq!(huge.checked_mul(&huge), Err(Error::ExponentOverflow));
+    assert_eq!(tiny.checked_mul(&tiny), Err(Error::ExponentOverflow));
+    assert_eq!(huge.checked_div(&tiny), Err(Error::ExponentOverflow));
+    assert_eq!(huge.checked_recip(), Ok(DBig::from_parts(ibig!(1), 10 - isize::MAX)));
+    assert_eq!(tiny.checked_recip(), Ok(DBig::from_parts(ibig!(1), isize::MAX - 9)));
+    assert_eq!(tiny.clone().with_precision(10).checked_sqrt(), Err(Error::ExponentOverflow));
+    assert_eq!(huge.checked_add(&huge), Ok(DBig::from_parts(ibig!(2), isize::MAX - 10)));
+    let top = DBig::from_parts(ibig!(5), isize::MAX);
+    assert_eq!(top.checked_add(&top), Err(Error::ExponentOverflow));
+    assert_eq!(huge.checked_mul(&tiny), Ok(DBig::from_parts(ibig!(1), -1)));
+
+    assert_eq!(DBig::checked_from_str("1.5e3"), Ok(DBig::from_parts(ibig!(15), 2)));
+    assert_eq!(DBig::checked_from_str("1.5x"), Err(Error::Parse(ParseError::InvalidDigit)));
+    assert_eq!(DBig::checked_from_str("1.5e-9223372036854775808"), Err(Error::ExponentOverflow));
+    assert_eq!(FloatRepr::<37, 0>::checked_from_str("1"), Err(Error::InvalidRadix));
+    assert_eq!(dbig("1", 10).checked_with_radix::<1>(), Err(Error::InvalidRadix));
+    assert_eq!(dbig("0.5", 10).checked_with_radix::<2>(), Ok(FBig::from(0.5f64).with_precision(33)));
+}
+
+#[test]
+fn test_exact_arithmetic() {
+    let sum = dbig("1e20", 1).add_exact(&dbig("1e-20", 1));
+    assert_eq!(sum, DBig::from_str("100000000000000000000.00000000000000000001").unwrap());
+    assert_eq!(sum.precision(), 41);
+    let diff = dbig("1e20", 1).sub_exact(&dbig("1e-20", 1));
+    assert_eq!(diff, DBig::from_str("99999999999999999999.99999999999999999999").unwrap());
+    assert_eq!(dbig("1.5", 10).add_exact(&dbig("-1.5", 2)).precision(), 10);
+    assert_eq!(dbig("0", 1).add_exact(&dbig("123", 3)).precision(), 3);
+
+    // the ledger balance is kept exact while the precision grows
+    let cent = dbig("0.01", 1);
+    let total = (0..100).fold(dbig("0", 1), |acc, _| acc.add_exact(&cent));
+    assert_eq!(total, dbig("1", 1));
+    assert_eq!(dbig("0.01", 1) + dbig("1000", 1), dbig("1000", 1));
+    assert_eq!(dbig("0.01", 1).add_exact(&dbig("1000", 1)).precision(), 6);
+
+    let product = dbig("1.5", 2).mul_exact(&dbig("1.5", 2));
+    assert_eq!(product, dbig("2.25", 3));
+    assert_eq!(product.precision(), 3);
+    assert_eq!(dbig("1.25", 3).mul_exact(&dbig("4", 10)).precision(), 10);
+
+    assert_eq!(dbig("1", 1).div_exact(&dbig("8", 1)), Ok(dbig("0.125", 3)));
+    assert_eq!(dbig("7", 1).div_exact(&dbig("-2.5", 2)), Ok(dbig("-2.8", 2)));
+    assert_eq!(dbig("3", 1).div_exact(&dbig("12", 2)).map(|v| v.precision()), Ok(2));
+    assert_eq!(dbig("1", 1).div_exact(&dbig("3", 1)), Err(Error::Inexact));
+    assert_eq!(dbig("1", 1).div_exact(&dbig("0", 1)), Err(Error::DivisionByZero));
+    assert_eq!(dbig("0", 1).div_exact(&dbig("3", 1)), Ok(dbig("0", 1)));
+    assert_eq!(FBig::from(3.).div_exact(&FBig::from(0.75)), Ok(FBig::from(4.)));
+    assert_eq!(FBig::from(1.).div_exact(&FBig::from(10.)), Err(Error::Inexact));
+    let quotient = FBig::from(1.).div_exact(&FBig::from(2f64.powi(-100) * 3.)).unwrap_err();
+    assert_eq!(quotient, Error::Inexact);
+    let quotient = FBig::from(1.).div_exact(&FBig::from(2f64.powi(100))).unwrap();
+    assert_eq!(quotient, FBig::from(2f64.powi(-100)));
+}
+
+#[test]
+fn test_precision_policy() {
+    use PrecisionPolicy::*;
+
+    let a = dbig("1.23456789", 9);
+    let b = dbig("1", 2);
+    assert_eq!(a.add_with_policy(&b, Max), dbig("2.23456789", 9));
+    assert_eq!(a.add_with_policy(&b, Max).precision(), 9);
+    assert_eq!(a.add_with_policy(&b, Min), dbig("2.2", 2));
+    assert_eq!(a.add_with_policy(&b, Min).precision(), 2);
+    assert_eq!(b.add_with_policy(&a, Left), dbig("2.2", 2));
+    assert_eq!(a.add_with_policy(&b, Fixed(4)), dbig("2.235", 4));
+    assert_eq!(a.sub_with_policy(&b, Min), dbig("0.23", 2));
+    assert_eq!(a.mul_with_policy(&dbig("3", 1), Min), dbig("4", 1));
+    assert_eq!(b.div_with_policy(&dbig("3", 20), Min), dbig("0.33", 2));
+    assert_eq!(b.div_with_policy(&dbig("3", 20), Fixed(5)).precision(), 5);
+
+    // the operands are not rounded before the operation
+    assert_eq!(dbig("1.45", 3).add_with_precision(&dbig("0.0049", 2), 2), dbig("1.5", 2));
+    assert_eq!(dbig("1", 1).sub_with_precision(&dbig("1e-20", 1), 25), dbig("0.99999999999999999999", 20));
+    assert_eq!(dbig("1.5", 2).mul_with_precision(&dbig("1.5", 2), 2), dbig("2.2", 2));
+    assert_eq!(dbig("2", 1).div_with_precision(&dbig("3", 1), 3), dbig("0.667", 3));
+    assert_eq!(PrecisionPolicy::Max.resolve(3, 5), 5);
+}
+
+#[test]
+fn test_hypot() {
+    assert_eq!(dbig("3", 30).hypot(&dbig("-4", 30)), dbig("5", 30));
+    assert_eq!(dbig("1", 30).hypot(&dbig("2", 30)), dbig("2.23606797749978969640917366873", 30));
+    assert_eq!(dbig("1.5e-700", 30).hypot(&dbig("2.5e-700", 30)), dbig("2.91547594742265023543707643877e-700", 30));
+    assert_eq!(dbig("3e100000", 30).hypot(&dbig("4e100000", 30)), dbig("5e100000", 30));
+    assert_eq!(dbig("1", 30).hypot(&dbig("1e-40", 30)), dbig("1", 30));
+    assert_eq!(dbig("0", 30).hypot(&dbig("0", 30)), dbig("0", 30));
+
+    let values = [dbig("0.1", 10), dbig("0.2", 10), dbig("-0.3", 10)];
+    assert_eq!(DBig::sum_of_squares(&values), dbig("0.14", 10));
+    assert_eq!(DBig::sum_of_squares(&[dbig("1e20", 5), dbig("1", 5)]), dbig("1e40", 5));
+}
+
+#[test]
+fn test_exp() {
+    assert_eq!(dbig("0", 30).exp(), dbig("1", 30));
+    assert_eq!(dbig("1", 30).exp(), dbig("2.71828182845904523536028747135", 30));
+    assert_eq!(dbig("-100", 20).exp(), dbig("3.7200759760208359630e-44", 20));
+}
+
+#[test]
+fn test_ln() {
+    assert_eq!(dbig("1", 30).ln(), dbig("0", 30));
+    assert_eq!(dbig("2", 30).ln(), dbig("0.693147180559945309417232121458", 30));
+    assert_eq!(dbig("0.5", 30).ln(), dbig("-0.693147180559945309417232121458", 30));
+    assert_eq!(dbig("1e1000", 30).ln(), dbig("2302.58509299404568401799145468", 30));
+    assert_eq!(dbig("1.0000000000000000000000000001", 30).ln(), dbig("9.9999999999999999999999999995e-29", 30));
+}
+
+#[test]
+fn test_ln_high_precision() {
+    // evaluated by the AGM
+    let ln10 = dbig("10", 10100).ln().to_string();
+    assert_eq!(ln10.len(), 10101);
+    assert!(ln10.starts_with("2.3025850929940456840179914546"));
+    assert!(ln10.ends_with("327780549038605149612591770322"));
+}
+
+#[test]
+fn test_erf() {
+    assert_eq!(dbig("0.5", 30).erf(), dbig("0.520499877813046537682746653892", 30));
+    assert_eq!(dbig("-0.5", 30).erf(), dbig("-0.520499877813046537682746653892", 30));
+    assert_eq!(dbig("20", 30).erf(), dbig("1", 30));
+    assert_eq!(dbig("0.5", 30).erfc(), dbig("0.479500122186953462317253346108", 30));
+    assert_eq!(dbig("-1", 30).erfc(), dbig("1.84270079294971486934122063508", 30));
+
+    // far tails
+    assert_eq!(dbig("9.5", 30).erfc(), dbig("3.76921448565487994167708732105e-41", 30));
+    assert_eq!(dbig("30", 30).erfc(), dbig("2.56465620375611160003339727750e-393", 30));
+}
+
+#[test]
+fn test_erf_inv() {
+    assert_eq!(dbig("0", 30).erf_inv(), dbig("0", 30));
+    assert_eq!(dbig("0.5", 30).erf_inv(), dbig("0.476936276204469873381418353643", 30));
+    assert_eq!(dbig("-0.5", 30).erf_inv(), dbig("-0.476936276204469873381418353643", 30));
+    assert_eq!(dbig("0.999999", 30).erf_inv(), dbig("3.45891073727950002215092763596", 30));
+    assert_eq!(dbig("1e-30", 30).erf_inv(), dbig("8.86226925452758013649083741671e-31", 30));
+
+    // far tails, where the newton step is scaled by 1/(1-y)
+    assert_eq!(dbig("0.9999999", 10).erf_inv(), dbig("3.766562582", 10));
+    assert_eq!(dbig("0.9999999999", 30).erf_inv(), dbig("4.57282496738948527874104367314", 30));
+    let y = dbig("1", 50) - dbig("1e-40", 50);
+    assert_eq!(y.erf_inv(), dbig("9.4487897667208582625031850541070900390659191139722", 50));
+
+    let x = dbig("1.25", 30);
+    assert_eq!(x.erf().erf_inv(), x);
+}
+
+#[test]
+#[should_panic]
+fn test_erf_inv_out_of_domain() {
+    let _ = dbig("1", 10).erf_inv();
+}
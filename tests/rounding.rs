@@ -0,0 +1,262 @@
+use std::str::FromStr;
+
+use ibig::ibig;
+use fbig::{DBig, FBig, DecimalRepr, Rounding, RoundingMode, Approximation, RoundingDirection};
+
+fn dbig(s: &str) -> DBig {
+    DBig::from_str(s).unwrap()
+}
+
+#[test]
+fn test_with_precision_rounded() {
+    assert_eq!(dbig("1.25").with_precision_rounded(2, Rounding::HalfEven), dbig("1.2"));
+    assert_eq!(dbig("1.25").with_precision_rounded(2, Rounding::HalfAway), dbig("1.3"));
+    assert_eq!(dbig("1.21").with_precision_rounded(2, Rounding::Up), dbig("1.3"));
+    assert_eq!(dbig("-1.21").with_precision_rounded(2, Rounding::Up), dbig("-1.2"));
+    assert_eq!(dbig("1.29").with_precision_rounded(2, Rounding::Down), dbig("1.2"));
+    assert_eq!(dbig("-1.21").with_precision_rounded(2, Rounding::Down), dbig("-1.3"));
+    assert_eq!(dbig("-1.29").with_precision_rounded(2, Rounding::Zero), dbig("-1.2"));
+
+    // the rounding doesn't change the type
+    let value: DBig = dbig("9.99").with_precision_rounded(2, Rounding::Up);
+    assert_eq!(value, dbig("10"));
+}
+
+#[test]
+fn test_operations_rounded() {
+    let one = dbig("1").with_precision(2);
+    let tiny = dbig("1e-10").with_precision(2);
+    assert_eq!(one.add_rounded(&tiny, Rounding::Up), dbig("1.1"));
+    assert_eq!(one.add_rounded(&tiny, Rounding::Down), dbig("1"));
+    assert_eq!(one.sub_rounded(&tiny, Rounding::Down), dbig("0.99"));
+    assert_eq!(one.sub_rounded(&tiny, Rounding::HalfEven), dbig("1"));
+
+    let three = dbig("3").with_precision(1);
+    let seven = dbig("7").with_precision(1);
+    assert_eq!(three.mul_rounded(&seven, Rounding::Down), dbig("20"));
+    assert_eq!(three.mul_rounded(&seven, Rounding::Up), dbig("30"));
+    assert_eq!(three.mul_rounded(&-seven.clone(), Rounding::Zero), dbig("-20"));
+
+    let one = dbig("1").with_precision(3);
+    assert_eq!(one.div_rounded(&three, Rounding::Up), dbig("0.334"));
+    assert_eq!(one.div_rounded(&three, Rounding::HalfEven), dbig("0.333"));
+    assert_eq!((-one).div_rounded(&three, Rounding::Down), dbig("-0.334"));
+}
+
+#[test]
+fn test_more_rounding_modes() {
+    let round = |s: &str, precision: usize, rounding: Rounding| dbig(s).with_precision_rounded(precision, rounding);
+
+    assert_eq!(round("1.25", 2, Rounding::HalfZero), dbig("1.2"));
+    assert_eq!(round("-1.25", 2, Rounding::HalfZero), dbig("-1.2"));
+    assert_eq!(round("1.251", 2, Rounding::HalfZero), dbig("1.3"));
+    assert_eq!(round("1.25", 2, Rounding::HalfUp), dbig("1.3"));
+    assert_eq!(round("-1.25", 2, Rounding::HalfUp), dbig("-1.2"));
+    assert_eq!(round("-1.249", 2, Rounding::HalfUp), dbig("-1.2"));
+    assert_eq!(round("1.201", 2, Rounding::AwayFromZero), dbig("1.3"));
+    assert_eq!(round("-1.201", 2, Rounding::AwayFromZero), dbig("-1.3"));
+    assert_eq!(round("1.35", 2, Rounding::HalfOdd), dbig("1.3"));
+    assert_eq!(round("1.25", 2, Rounding::HalfOdd), dbig("1.3"));
+    assert_eq!(round("1.26", 2, Rounding::HalfOdd), dbig("1.3"));
+    assert_eq!(round("1.29", 2, Rounding::ToOdd), dbig("1.3"));
+    assert_eq!(round("1.31", 2, Rounding::ToOdd), dbig("1.3"));
+    assert_eq!(round("-1.21", 2, Rounding::ToOdd), dbig("-1.3"));
+    assert_eq!(round("1.2", 2, Rounding::ToOdd), dbig("1.2"));
+
+    // rounding to odd first prevents the error of double rounding
+    let x = dbig("1.2500001");
+    assert_eq!(x.clone().with_precision(4).with_precision(2), dbig("1.2"));
+    assert_eq!(x.with_precision_rounded(4, Rounding::ToOdd).with_precision(2), dbig("1.3"));
+}
+
+#[test]
+fn test_rounding_mode_operations() {
+    type HalfUpDBig = DecimalRepr<{RoundingMode::HalfUp}>;
+    type ToOddDBig = DecimalRepr<{RoundingMode::ToOdd}>;
+    let parse = |s: &str, precision: usize| HalfUpDBig::from_str(s).unwrap().with_precision(precision);
+
+    assert_eq!(parse("1", 2) / parse("8", 2), parse("0.13", 2));
+    assert_eq!(parse("-1", 2) / parse("8", 2), parse("-0.12", 2));
+    assert_eq!(parse("1.5", 2) * parse("-0.5", 2), parse("-0.75", 2));
+    assert_eq!(parse("0.5", 1) * parse("-0.5", 1), parse("-0.2", 1));
+
+    let one = ToOddDBig::from_str("1").unwrap().with_precision(3);
+    let tiny = ToOddDBig::from_str("1e-20").unwrap();
+    assert_eq!(one.clone() + tiny.clone(), ToOddDBig::from_str("1.01").unwrap());
+    assert_eq!(one - tiny, ToOddDBig::from_str("0.999").unwrap());
+}
+
+#[test]
+fn test_display_rounding() {
+    type HalfUpDBig = DecimalRepr<{RoundingMode::HalfUp}>;
+    type UpDBig = DecimalRepr<{RoundingMode::Up}>;
+    type DownDBig = DecimalRepr<{RoundingMode::Down}>;
+
+    assert_eq!(format!("{:.2}", HalfUpDBig::from_str("-0.125").unwrap()), "-0.12");
+    assert_eq!(format!("{:.2}", HalfUpDBig::from_str("0.125").unwrap()), "0.13");
+    assert_eq!(format!("{:.1}", UpDBig::from_str("0.96").unwrap()), "1.0");
+    assert_eq!(format!("{:.1}", UpDBig::from_str("-0.96").unwrap()), "-0.9");
+    assert_eq!(format!("{:.1}", DownDBig::from_str("-0.96").unwrap()), "-1.0");
+    assert_eq!(format!("{:.0}", DownDBig::from_str("-0.96").unwrap()), "-1");
+
+    // all the discarded digits are considered
+    assert_eq!(format!("{:.2}", dbig("0.1251")), "0.13");
+    assert_eq!(format!("{:.2}", dbig("0.125")), "0.12");
+    assert_eq!(format!("{:.3}", dbig("9.9996")), "10.000");
+    assert_eq!(format!("{:.4}", dbig("-0.01")), "-0.0100");
+    assert_eq!(format!("{:.1}", dbig("-0.001")), "-0.0");
+}
+
+#[test]
+#[cfg(feature = "rand_core")]
+fn test_stochastic_rounding() {
+    use rand_xorshift::XorShiftRng;
+    use rand_core::SeedableRng;
+
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let count_up = |s: &str, rng: &mut XorShiftRng| {
+        (0..1000).filter(|_| {
+            let rounded = dbig(s).with_precision_stochastic(2, rng);
+            assert!(rounded == dbig("1.3") || rounded == dbig("1.2") || rounded == dbig("-1.3") || rounded == dbig("-1.2"));
+            rounded.abs() == dbig("1.3")
+        }).count()
+    };
+    let up = count_up("1.25", &mut rng);
+    assert!((400..600).contains(&up));
+    let up = count_up("1.21", &mut rng);
+    assert!((50..150).contains(&up));
+    let up = count_up("-1.2999", &mut rng);
+    assert!((950..=1000).contains(&up));
+
+    // exact values are not changed
+    assert_eq!(dbig("1.2").with_precision_stochastic(2, &mut rng), dbig("1.2"));
+
+    // the result is deterministic given the seed
+    let sample = |seed| {
+        let mut rng = XorShiftRng::seed_from_u64(seed);
+        (0..20).map(|_| dbig("1.234567").with_precision_stochastic(3, &mut rng)).collect::<Vec<_>>()
+    };
+    assert_eq!(sample(7), sample(7));
+}
+
+#[test]
+fn test_approximation() {
+    use Approximation::*;
+    use RoundingDirection::*;
+
+    assert_eq!(dbig("1.25").with_precision_approx(3), Exact(dbig("1.25")));
+    assert_eq!(dbig("1.25").with_precision_approx(2), Inexact(dbig("1.2"), Down));
+    assert_eq!(dbig("-1.25").with_precision_approx(2), Inexact(dbig("-1.2"), Up));
+    assert_eq!(dbig("1.26").with_precision_approx(2), Inexact(dbig("1.3"), Up));
+    assert_eq!(dbig("-1.26").with_precision_approx(2), Inexact(dbig("-1.3"), Down));
+    assert_eq!(dbig("1.2000").with_precision_approx(2), Exact(dbig("1.2")));
+
+    // arithmetic operations
+    let one = dbig("1").with_precision(3);
+    let three = dbig("3").with_precision(3);
+    assert_eq!(one.div_approx(&three), Inexact(dbig("0.333"), Down));
+    assert_eq!((-&one).div_approx(&three), Inexact(dbig("-0.333"), Up));
+    assert_eq!(three.div_approx(&dbig("0.5")), Exact(dbig("6")));
+    assert_eq!(one.add_approx(&dbig("1e-10")), Inexact(dbig("1"), Down));
+    assert_eq!(one.sub_approx(&dbig("1e-10")), Inexact(dbig("1"), Up));
+    assert_eq!(one.add_approx(&dbig("0.01")), Exact(dbig("1.01")));
+    assert_eq!(three.mul_approx(&dbig("0.334")), Inexact(dbig("1"), Down));
+    assert_eq!(three.mul_approx(&dbig("0.5")), Exact(dbig("1.5")));
+    assert_eq!(dbig("2").with_precision(5).sqrt_approx(), Inexact(dbig("1.4142"), Down));
+    assert_eq!(dbig("3").with_precision(5).sqrt_approx(), Inexact(dbig("1.7321"), Up));
+    assert_eq!(dbig("0.0625").sqrt_approx(), Exact(dbig("0.25")));
+
+    // conversions
+    assert_eq!(dbig("0.5").with_radix_approx::<2>(), Exact(FBig::from(0.5f64)));
+    assert!(!dbig("0.1").with_radix_approx::<2>().is_exact());
+    assert_eq!(dbig("0.5").to_f64_approx(), Exact(0.5));
+    assert_eq!(dbig("0.1").to_f64_approx(), Inexact(0.1, Up));
+    assert_eq!(dbig("0.1").to_f32_approx(), Inexact(0.1f32, Up));
+    assert_eq!(dbig("1e400").to_f64_approx(), Inexact(f64::INFINITY, Up));
+    assert_eq!(dbig("2.5").to_int_approx(), Inexact(ibig!(2), Down));
+    assert_eq!(dbig("-2.5").to_int_approx(), Inexact(ibig!(-2), Up));
+    assert_eq!(dbig("1e3").to_int_approx(), Exact(ibig!(1000)));
+}
+
+#[test]
+fn test_to_float_boundaries() {
+    type FBigZero = fbig::BinaryRepr<{RoundingMode::Zero}>;
+    type FBigUp = fbig::BinaryRepr<{RoundingMode::Up}>;
+    type FBigDown = fbig::BinaryRepr<{RoundingMode::Down}>;
+    let min_subnormal = f64::from_bits(1);
+
+    // subnormal numbers are rounded once at the lowest digit
+    assert_eq!(FBig::from_parts((ibig!(1) << 60) + 1u8, -1135).to_f64(), min_subnormal);
+    assert_eq!(FBig::from_parts(ibig!(1), -1075).to_f64(), 0.);
+    assert_eq!(FBig::from_parts(ibig!(3), -1075).to_f64(), 2. * min_subnormal);
+    assert!(FBig::from_parts(ibig!(-1), -1076).to_f64().is_sign_negative());
+    assert_eq!(FBig::from(1.5e-320).to_f64(), 1.5e-320);
+    assert_eq!(FBig::from_parts((ibig!(1) << 53) - 1u8, -1075).to_f64(), f64::MIN_POSITIVE);
+    assert_eq!(FBig::from_parts(ibig!(1), -1022).to_f64(), f64::MIN_POSITIVE);
+    assert_eq!(dbig("2.4703282292062328e-324").to_f64(), min_subnormal);
+    assert_eq!(dbig("2.4703282292062327e-324").to_f64(), 0.);
+    assert_eq!(dbig("1e-400").to_f64(), 0.);
+    assert_eq!(FBig::from_parts((ibig!(1) << 40) + 1u8, -190).to_f32(), f32::from_bits(1));
+    assert_eq!(dbig("1.17549435e-38").to_f32(), f32::MIN_POSITIVE);
+
+    // the directed roundings reach the smallest subnormal number but not zero
+    assert_eq!(FBigUp::from_parts(ibig!(1), -2000).to_f64(), min_subnormal);
+    assert_eq!(FBigDown::from_parts(ibig!(-1), -2000).to_f64(), -min_subnormal);
+    assert_eq!(FBigZero::from_parts(ibig!(1), -2000).to_f64(), 0.);
+    assert_eq!(FBigUp::from_parts(ibig!(1), -200).to_f32(), f32::from_bits(1));
+
+    // overflow gives infinity or the largest finite number depending on the rounding
+    let half_above_max = FBig::from_parts((ibig!(1) << 54) - 1u8, 970);
+    assert_eq!(FBig::from(f64::MAX).to_f64(), f64::MAX);
+    assert_eq!(half_above_max.to_f64(), f64::INFINITY);
+    assert_eq!(half_above_max.clone().with_rounding::<{RoundingMode::Zero}>().to_f64(), f64::MAX);
+    assert_eq!(FBigUp::from_parts(ibig!(1), 2000).to_f64(), f64::INFINITY);
+    assert_eq!(FBigDown::from_parts(ibig!(1), 2000).to_f64(), f64::MAX);
+    assert_eq!(FBigUp::from_parts(ibig!(-1), 2000).to_f64(), -f64::MAX);
+    assert_eq!(FBigDown::from_parts(ibig!(-1), 2000).to_f64(), f64::NEG_INFINITY);
+    assert_eq!(dbig("1e39").to_f32(), f32::INFINITY);
+    assert_eq!(dbig("-1e39").with_rounding::<{RoundingMode::Zero}>().to_f32(), -f32::MAX);
+}
+
+#[test]
+fn test_quantize() {
+    let x = dbig("1234.5678");
+    assert_eq!(x.round_dp(2), dbig("1234.57"));
+    assert_eq!(x.round_dp(2).precision(), 8);
+    assert_eq!(x.round_dp(10), x);
+    assert_eq!(x.quantize(0), dbig("1235"));
+    assert_eq!(x.quantize(-2), dbig("1200"));
+    assert_eq!(x.quantize(-4), dbig("0"));
+    assert_eq!(x.quantize_rounded(-4, Rounding::Up), dbig("10000"));
+    assert_eq!(dbig("2.5").round_dp(0), dbig("2"));
+    assert_eq!(dbig("2.5").quantize_rounded(0, Rounding::HalfAway), dbig("3"));
+    assert_eq!(dbig("-1.005").round_dp(2), dbig("-1"));
+    assert_eq!(dbig("-1.005").quantize_rounded(2, Rounding::Down), dbig("-1.01"));
+    assert_eq!(dbig("9.996").round_dp(2), dbig("10"));
+
+    assert_eq!(x.to_fixed_scale(2), "1234.57");
+    assert_eq!(x.to_fixed_scale(6), "1234.567800");
+    assert_eq!(x.to_fixed_scale(0), "1235");
+    assert_eq!(dbig("12").to_fixed_scale(2), "12.00");
+    assert_eq!(dbig("1e3").to_fixed_scale(0), "1000");
+    assert_eq!(dbig("0").to_fixed_scale(3), "0.000");
+    assert_eq!(dbig("0.051").to_fixed_scale(1), "0.1");
+    assert_eq!(dbig("0.05").to_fixed_scale(1), "0.0");
+    assert_eq!(dbig("-0.001").to_fixed_scale(2), "0.00");
+    assert_eq!(dbig("-1.5").to_fixed_scale(3), "-1.500");
+    assert_eq!(dbig("-0.0123").to_fixed_scale(3), "-0.012");
+    assert_eq!(FBig::from(0.75).to_fixed_scale(3), "0.110");
+}
+
+#[test]
+fn test_round_sig() {
+    let x = dbig("123.456").with_precision(10);
+    assert_eq!(x.round_sig(4), dbig("123.5"));
+    assert_eq!(x.round_sig(4).precision(), 10);
+    assert_eq!(x.round_sig(1), dbig("100"));
+    assert_eq!(x.round_sig(10), x);
+    assert_eq!(dbig("-0.0012345").round_sig(3), dbig("-0.00123"));
+    assert_eq!(dbig("9.96").round_sig(2), dbig("10"));
+    assert_eq!(dbig("2.5").round_sig(1), dbig("2"));
+    assert_eq!(FBig::from(0.8125).round_sig(2), FBig::from(0.75));
+}
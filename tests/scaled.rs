@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use ibig::ibig;
+use fbig::{DBig, ScaledDBig};
+
+fn sdbig(s: &str) -> ScaledDBig {
+    ScaledDBig::from_str(s).unwrap()
+}
+
+fn sdbig_prec(s: &str, precision: usize) -> ScaledDBig {
+    sdbig(s).with_precision(precision)
+}
+
+#[test]
+fn test_scaled_parse_print() {
+    for s in ["1.50", "0.00", "-0.10", "100", "1E+2", "1.50E+3", "0.000001", "1E-7", "-12.3400"] {
+        assert_eq!(sdbig(s).to_string(), s);
+    }
+    assert_eq!(sdbig("0.0000001").to_string(), "1E-7");
+    assert_eq!(sdbig("1.50").precision(), ScaledDBig::MIN_PRECISION);
+    assert_eq!(sdbig("1.50").with_precision(3).precision(), 3);
+    assert_eq!(sdbig("1.50").into_parts(), (ibig!(150), -2));
+    assert_eq!(sdbig("1.50").value(), &DBig::from_str("1.5").unwrap());
+    assert_eq!(ScaledDBig::from(DBig::from_str("1.50").unwrap()).to_string(), "1.5");
+    assert_eq!(format!("{:.3}", sdbig("1.5")), "1.500");
+    assert_eq!(format!("{:.1}", sdbig("1.25")), "1.2");
+
+    // the comparison only depends on the value
+    assert_eq!(sdbig("1.5"), sdbig("1.50"));
+    assert!(sdbig("1.5") < sdbig("1.51"));
+    assert!(!sdbig("1.5").same_quantum(&sdbig("1.50")));
+    assert!(sdbig("1.5").same_quantum(&sdbig("2.0")));
+}
+
+#[test]
+fn test_scaled_arithmetic() {
+    let p = 28;
+    assert_eq!((sdbig_prec("1.50", p) + sdbig("2.5")).to_string(), "4.00");
+    assert_eq!((sdbig_prec("1.50", p) - sdbig("1.50")).to_string(), "0.00");
+    assert_eq!((sdbig_prec("1.50", p) * sdbig("2.0")).to_string(), "3.000");
+    assert_eq!((sdbig_prec("2.40", p) / sdbig("2")).to_string(), "1.20");
+    assert_eq!((sdbig_prec("1", p) / sdbig("4")).to_string(), "0.25");
+    assert_eq!((sdbig_prec("100", p) / sdbig("4")).to_string(), "25");
+    assert_eq!(sdbig_prec("1.00", p).sqrt().to_string(), "1.0");
+    assert_eq!(sdbig_prec("0.0400", p).sqrt().to_string(), "0.20");
+    assert_eq!((-sdbig("1.50")).to_string(), "-1.50");
+
+    // inexact results use all the digits
+    assert_eq!((sdbig_prec("1.00", 5) / sdbig_prec("3", 5)).to_string(), "0.33333");
+    assert_eq!(sdbig_prec("2", 5).sqrt().to_string(), "1.4142");
+
+    // short literals still have the minimum precision
+    assert_eq!(sdbig("2").sqrt().to_string(), "1.414213562373095048801688724");
+    assert_eq!((sdbig("1") / sdbig("3")).to_string(), "0.3333333333333333333333333333");
+    assert_eq!((sdbig("0.00") + sdbig("2")).sqrt().to_string(), "1.414213562373095048801688724");
+    assert_eq!((sdbig("0.10") / sdbig("7")).to_string(), "0.01428571428571428571428571429");
+    assert_eq!((sdbig_prec("1E+2", 5) * sdbig_prec("1E+3", 5)).to_string(), "1E+5");
+    assert_eq!((sdbig_prec("1.2E+3", 5) + sdbig_prec("1", 5)).to_string(), "1201");
+
+    // exact results that don't fit the precision
+    assert_eq!((sdbig_prec("1.50", 3) + sdbig_prec("100", 3)).to_string(), "102");
+    assert_eq!((sdbig_prec("12E+3", 3) / sdbig_prec("4", 3)).to_string(), "3E+3");
+    assert_eq!((sdbig_prec("5", 3) / sdbig_prec("2", 3)).to_string(), "2.5");
+
+    // invoice lines keep the cents
+    let price = sdbig_prec("19.99", 20);
+    let total = &(&price * &sdbig("3")) + &sdbig("0.01");
+    assert_eq!(total.to_string(), "59.98");
+    let tax = (&total * &sdbig("0.0825")).quantize(2);
+    assert_eq!(tax.to_string(), "4.95");
+    assert_eq!((total + tax).to_string(), "64.93");
+}
+
+#[test]
+fn test_scaled_quantize() {
+    assert_eq!(sdbig("1.5").quantize(2).to_string(), "1.50");
+    assert_eq!(sdbig_prec("1.5", 2).quantize(2).precision(), 3);
+    assert_eq!(sdbig("1.005").quantize(2).to_string(), "1.00");
+    assert_eq!(sdbig("123.456").quantize(-1).to_string(), "1.2E+2");
+    assert_eq!(sdbig("0.001").quantize(1).to_string(), "0.0");
+    assert_eq!(sdbig("1.500").with_precision(2).to_string(), "1.5");
+    assert_eq!(sdbig("1.500").with_precision(10).to_string(), "1.500");
+    assert_eq!(sdbig("1.500").normalize().to_string(), "1.5");
+    assert_eq!(sdbig("0.00").normalize().to_string(), "0");
+}
@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use fbig::DBig;
+
+fn dbig(s: &str, precision: usize) -> DBig {
+    DBig::from_str(s).unwrap().with_precision(precision)
+}
+
+#[test]
+fn test_zeta() {
+    assert_eq!(dbig("0", 30).zeta(), dbig("-0.5", 30));
+    assert_eq!(dbig("2", 30).zeta(), dbig("1.64493406684822643647241516665", 30));
+    assert_eq!(dbig("3", 30).zeta(), dbig("1.20205690315959428539973816151", 30));
+    assert_eq!(dbig("0.5", 30).zeta(), dbig("-1.46035450880958681288949915252", 30));
+    assert_eq!(dbig("-2.5", 30).zeta(), dbig("0.00851692877785033054235856702834", 30));
+    assert_eq!(dbig("-3", 30).zeta(), dbig("0.00833333333333333333333333333333", 30));
+    assert_eq!(dbig("-4", 30).zeta(), dbig("0", 30));
+    assert_eq!(dbig("1000", 30).zeta(), dbig("1", 30));
+
+    // large negative arguments, where the direct summation would cancel
+    assert_eq!(dbig("-50.5", 30).zeta(), dbig("2399094238135732095670783.28579", 30));
+    assert_eq!(dbig("-49.5", 30).zeta(), dbig("-298494132031557239420707.68766", 30));
+    assert_eq!(dbig("-50.25", 30).zeta(), dbig("769695928303412421478296.416362", 30));
+    assert_eq!(dbig("-3.999", 30).zeta(), dbig("0.00000798667836151706374690729682425", 30));
+}
+
+#[test]
+#[should_panic]
+fn test_zeta_pole() {
+    let _ = dbig("1", 10).zeta();
+}
+
+#[test]
+fn test_polylog() {
+    assert_eq!(dbig("0.9", 30).polylog(2), dbig("1.29971472300495872517106049419", 30));
+    assert_eq!(dbig("-0.7", 30).polylog(3), dbig("-0.648666321285235493506694417024", 30));
+    assert_eq!(dbig("-3", 30).polylog(2), dbig("-1.93937542076670895307727171918", 30));
+    assert_eq!(dbig("1", 30).polylog(2), dbig("2", 30).zeta());
+
+    // x = -1 reaches x^2 = 1 in the duplication formula
+    assert_eq!(dbig("-1", 30).polylog(2), dbig("-0.822467033424113218236207583323", 30));
+    assert_eq!(dbig("-1", 30).polylog(3), dbig("-0.901542677369695714049803621134", 30));
+    assert_eq!(dbig("-1", 30).polylog(5), dbig("-0.972119770446909305935655143553", 30));
+    assert_eq!(dbig("-0.999999", 30).polylog(3), dbig("-0.901541854902597629999237877603", 30));
+    assert_eq!(dbig("0.999999", 30).polylog(3), dbig("1.20205525823236273236164334537", 30));
+    assert_eq!(dbig("-1.000001", 30).polylog(4), dbig("-0.947033731039883749455049731325", 30));
+    assert_eq!(dbig("0.99", 30).polylog(4), dbig("1.07032414616522915186966927553", 30));
+    assert_eq!(dbig("-0.99", 30).polylog(6), dbig("-0.975828638464401089743389127909", 30));
+    assert_eq!(dbig("0.5", 30).polylog(1), dbig("2", 30).ln());
+
+    // negative orders are rational functions
+    assert_eq!(dbig("0.5", 30).polylog(-2), dbig("6", 30));
+    assert_eq!(dbig("-2", 30).polylog(-3), dbig("0.0740740740740740740740740740741", 30));
+}
+
+#[test]
+fn test_lambert_w() {
+    assert_eq!(dbig("0", 30).lambert_w(), dbig("0", 30));
+    assert_eq!(dbig("1", 30).lambert_w(), dbig("0.56714329040978387299996866221", 30));
+    assert_eq!(dbig("100", 30).lambert_w(), dbig("3.38563014029005018488824436453", 30));
+    assert_eq!(dbig("-0.3678", 30).lambert_w(), dbig("-0.979360714957828477476184443489", 30));
+
+    assert_eq!(dbig("-0.3", 30).lambert_w_m1(), dbig("-1.78133702342162761197417028151", 30));
+    assert_eq!(dbig("-1e-10", 30).lambert_w_m1(), dbig("-26.2952388192469256941101288219", 30));
+}
+
+#[test]
+#[should_panic]
+fn test_lambert_w_out_of_domain() {
+    let _ = dbig("-0.5", 10).lambert_w();
+}
+
+#[test]
+fn test_agm() {
+    assert_eq!(dbig("1", 30).agm(&dbig("2", 30)), dbig("1.45679103104690686918643238327", 30));
+    assert_eq!(dbig("0.001", 30).agm(&dbig("1000", 30)), dbig("103.329593765709410227238377016", 30));
+    assert_eq!(dbig("0", 30).agm(&dbig("2", 30)), dbig("0", 30));
+}
+
+#[test]
+fn test_ellip() {
+    assert_eq!(dbig("0", 30).ellip_k(), dbig("1.57079632679489661923132169164", 30));
+    assert_eq!(dbig("0.5", 30).ellip_k(), dbig("1.85407467730137191843385034720", 30));
+    assert_eq!(dbig("0.999999", 30).ellip_k(), dbig("8.29405146361543998531551927880", 30));
+    assert_eq!(dbig("-3", 30).ellip_k(), dbig("1.07825782374982161771933749940", 30));
+
+    assert_eq!(dbig("0.5", 30).ellip_e(), dbig("1.35064388104767550252017473534", 30));
+    assert_eq!(dbig("0.999999", 30).ellip_e(), dbig("1.00000389702617206115268911841", 30));
+    assert_eq!(dbig("-1000000", 30).ellip_e(), dbig("1000.00439702434854808228326821", 30));
+    assert_eq!(dbig("1", 30).ellip_e(), dbig("1", 30));
+}
+
+#[test]
+#[should_panic]
+fn test_ellip_k_out_of_domain() {
+    let _ = dbig("1", 10).ellip_k();
+}